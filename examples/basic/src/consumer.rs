@@ -17,14 +17,7 @@ pub async fn start_consumer(station: &MemphisStation) -> Result<MemphisConsumer,
     tokio::spawn(async move {
         while let Some(msg) = receiver.recv().await {
             // Do something with the message here.
-            let json_data = match msg.get_data_as_string() {
-                Ok(x) => x,
-                Err(e) => {
-                    error!("Error while getting data as string: {:?}", e);
-                    continue;
-                }
-            };
-            let log_message: LogData = match serde_json::from_str(&json_data) {
+            let log_message: LogData = match msg.deserialize() {
                 Ok(x) => x,
                 Err(e) => {
                     error!("Error while deserializing message: {:?}", e);
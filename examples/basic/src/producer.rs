@@ -1,7 +1,7 @@
 use anyhow::Error;
 use chrono::{DateTime, Utc};
 use log::error;
-use memphis_rust_community::producer::{ComposableMessage, MemphisProducerOptions};
+use memphis_rust_community::producer::{ComposableMessage, MemphisProducerOptions, TypedProducer};
 use memphis_rust_community::station::MemphisStation;
 use serde::{Deserialize, Serialize};
 use std::time::Duration;
@@ -24,7 +24,8 @@ pub struct LogData {
 pub async fn start_producer(station: &MemphisStation) -> Result<JoinHandle<()>, Error> {
     let producer_options =
         MemphisProducerOptions::new("amazing-service").with_generate_unique_suffix(true);
-    let mut producer = station.create_producer(producer_options).await?;
+    let producer = station.create_producer(producer_options).await?;
+    let mut producer = TypedProducer::<LogData>::new(producer);
 
     let handle = tokio::spawn(async move {
         let mut counter = 0;
@@ -43,17 +44,8 @@ pub async fn start_producer(station: &MemphisStation) -> Result<JoinHandle<()>,
                 message: format!("Something incredible happened we counted to: {}", counter),
                 date: Utc::now(),
             };
-            let json_message = match serde_json::to_string(&message) {
-                Ok(x) => x,
-                Err(_) => {
-                    error!("Error while serializing message: {:?}", message);
-                    continue;
-                }
-            };
-            let composable_message = ComposableMessage::new()
-                .with_payload(json_message)
-                .with_msg_id(counter.to_string());
-            match producer.produce(composable_message).await {
+            let composable_message = ComposableMessage::new().with_msg_id(counter.to_string());
+            match producer.produce_message(&message, composable_message).await {
                 Ok(x) => {
                     if let Err(e) = x.await {
                         error!("Error while awaiting ack: {:?}", e);
@@ -0,0 +1,29 @@
+use bytes::Bytes;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::codec::{Codec, CodecError};
+
+/// Encodes values as CBOR.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct CborCodec;
+
+impl CborCodec {
+    pub const CONTENT_TYPE: &'static str = "application/cbor";
+}
+
+impl Codec for CborCodec {
+    fn content_type(&self) -> &'static str {
+        Self::CONTENT_TYPE
+    }
+
+    fn encode<T: Serialize + ?Sized>(&self, value: &T) -> Result<Bytes, CodecError> {
+        let mut payload = Vec::new();
+        ciborium::into_writer(value, &mut payload)?;
+        Ok(payload.into())
+    }
+
+    fn decode<T: DeserializeOwned>(&self, payload: &[u8]) -> Result<T, CodecError> {
+        Ok(ciborium::from_reader(payload)?)
+    }
+}
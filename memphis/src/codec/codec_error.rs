@@ -0,0 +1,27 @@
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum CodecError {
+    /// No codec for the content type of the message is available, its feature may not be enabled.
+    #[error("Unsupported content type '{0}'")]
+    UnsupportedContentType(String),
+
+    #[error("JSON error: {0}")]
+    JsonError(#[from] serde_json::Error),
+
+    #[cfg(feature = "codec_msgpack")]
+    #[error("MessagePack encode error: {0}")]
+    MsgPackEncodeError(#[from] rmp_serde::encode::Error),
+
+    #[cfg(feature = "codec_msgpack")]
+    #[error("MessagePack decode error: {0}")]
+    MsgPackDecodeError(#[from] rmp_serde::decode::Error),
+
+    #[cfg(feature = "codec_cbor")]
+    #[error("CBOR encode error: {0}")]
+    CborEncodeError(#[from] ciborium::ser::Error<std::io::Error>),
+
+    #[cfg(feature = "codec_cbor")]
+    #[error("CBOR decode error: {0}")]
+    CborDecodeError(#[from] ciborium::de::Error<std::io::Error>),
+}
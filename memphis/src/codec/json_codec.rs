@@ -0,0 +1,27 @@
+use bytes::Bytes;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::codec::{Codec, CodecError};
+
+/// Encodes values as JSON. This is the format expected by the schemaverse JSON and Avro validators.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct JsonCodec;
+
+impl JsonCodec {
+    pub const CONTENT_TYPE: &'static str = "application/json";
+}
+
+impl Codec for JsonCodec {
+    fn content_type(&self) -> &'static str {
+        Self::CONTENT_TYPE
+    }
+
+    fn encode<T: Serialize + ?Sized>(&self, value: &T) -> Result<Bytes, CodecError> {
+        Ok(serde_json::to_vec(value)?.into())
+    }
+
+    fn decode<T: DeserializeOwned>(&self, payload: &[u8]) -> Result<T, CodecError> {
+        Ok(serde_json::from_slice(payload)?)
+    }
+}
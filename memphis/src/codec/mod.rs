@@ -0,0 +1,52 @@
+//! Codecs to convert values from and to message payloads.
+//!
+//! Messages encoded by a codec carry its content type in the `content-type` header,
+//! so consumers know how to decode them.
+use bytes::Bytes;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+#[cfg(feature = "codec_cbor")]
+mod cbor_codec;
+mod codec_error;
+mod json_codec;
+#[cfg(feature = "codec_msgpack")]
+mod msgpack_codec;
+
+#[cfg(feature = "codec_cbor")]
+pub use cbor_codec::*;
+pub use codec_error::*;
+pub use json_codec::*;
+#[cfg(feature = "codec_msgpack")]
+pub use msgpack_codec::*;
+
+pub trait Codec: Send + Sync {
+    /// The content type of the encoded payloads, sent in the `content-type` header.
+    fn content_type(&self) -> &'static str;
+
+    fn encode<T: Serialize + ?Sized>(&self, value: &T) -> Result<Bytes, CodecError>;
+
+    fn decode<T: DeserializeOwned>(&self, payload: &[u8]) -> Result<T, CodecError>;
+}
+
+/// Decodes a payload with the codec matching its content type.
+/// Payloads without a content type are expected to be JSON.
+pub fn decode_with_content_type<T: DeserializeOwned>(
+    content_type: Option<&str>,
+    payload: &[u8],
+) -> Result<T, CodecError> {
+    let content_type = content_type
+        .and_then(|c| c.split(';').next())
+        .map(|c| c.trim().to_ascii_lowercase());
+
+    match content_type.as_deref() {
+        None | Some("") | Some(JsonCodec::CONTENT_TYPE) => JsonCodec.decode(payload),
+        #[cfg(feature = "codec_msgpack")]
+        Some(MsgPackCodec::CONTENT_TYPE) | Some("application/x-msgpack") => {
+            MsgPackCodec.decode(payload)
+        }
+        #[cfg(feature = "codec_cbor")]
+        Some(CborCodec::CONTENT_TYPE) => CborCodec.decode(payload),
+        Some(other) => Err(CodecError::UnsupportedContentType(other.to_string())),
+    }
+}
@@ -0,0 +1,27 @@
+use bytes::Bytes;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::codec::{Codec, CodecError};
+
+/// Encodes values as MessagePack. Structs are encoded as maps, so fields can be added or reordered.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct MsgPackCodec;
+
+impl MsgPackCodec {
+    pub const CONTENT_TYPE: &'static str = "application/msgpack";
+}
+
+impl Codec for MsgPackCodec {
+    fn content_type(&self) -> &'static str {
+        Self::CONTENT_TYPE
+    }
+
+    fn encode<T: Serialize + ?Sized>(&self, value: &T) -> Result<Bytes, CodecError> {
+        Ok(rmp_serde::to_vec_named(value)?.into())
+    }
+
+    fn decode<T: DeserializeOwned>(&self, payload: &[u8]) -> Result<T, CodecError> {
+        Ok(rmp_serde::from_slice(payload)?)
+    }
+}
@@ -1,3 +1,5 @@
+use std::fmt::{Display, Formatter};
+
 use async_nats::header::IntoHeaderName;
 use async_nats::HeaderName;
 
@@ -10,9 +12,9 @@ pub(crate) enum MemphisSpecialStation {
     ConsumerDestructions,
     StationDestructions,
 
-    #[allow(dead_code)]
+    #[cfg(feature = "schemaverse")]
     SchemaAttachments,
-    #[allow(dead_code)]
+    #[cfg(feature = "schemaverse")]
     SchemaDetachments,
 
     #[cfg(feature = "schemaverse")]
@@ -23,28 +25,31 @@ pub(crate) enum MemphisSpecialStation {
     PmAcks,
 }
 
-impl ToString for MemphisSpecialStation {
-    fn to_string(&self) -> String {
-        match self {
-            Self::ProducerCreations => String::from("$memphis_producer_creations"),
-            Self::ConsumerCreations => String::from("$memphis_consumer_creations"),
-            Self::StationCreations => String::from("$memphis_station_creations"),
+impl Display for MemphisSpecialStation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Self::ProducerCreations => "$memphis_producer_creations",
+            Self::ConsumerCreations => "$memphis_consumer_creations",
+            Self::StationCreations => "$memphis_station_creations",
 
-            Self::ProducerDestructions => String::from("$memphis_producer_destructions"),
-            Self::ConsumerDestructions => String::from("$memphis_consumer_destructions"),
-            Self::StationDestructions => String::from("$memphis_station_destructions"),
+            Self::ProducerDestructions => "$memphis_producer_destructions",
+            Self::ConsumerDestructions => "$memphis_consumer_destructions",
+            Self::StationDestructions => "$memphis_station_destructions",
 
-            Self::SchemaAttachments => String::from("$memphis_schema_attachments"),
-            Self::SchemaDetachments => String::from("$memphis_schema_detachments"),
+            #[cfg(feature = "schemaverse")]
+            Self::SchemaAttachments => "$memphis_schema_attachments",
+            #[cfg(feature = "schemaverse")]
+            Self::SchemaDetachments => "$memphis_schema_detachments",
 
             #[cfg(feature = "schemaverse")]
-            Self::Notifications => String::from("$memphis_notifications"),
+            Self::Notifications => "$memphis_notifications",
 
             #[cfg(feature = "schemaverse")]
-            Self::MemphisSchemaverseDls => String::from("$memphis_schemaverse_dls"),
+            Self::MemphisSchemaverseDls => "$memphis_schemaverse_dls",
 
-            Self::PmAcks => String::from("$memphis_pm_acks"),
-        }
+            Self::PmAcks => "$memphis_pm_acks",
+        };
+        f.write_str(name)
     }
 }
 
@@ -52,6 +57,7 @@ pub(crate) enum MemphisHeaders {
     MessageId,
     MemphisProducedBy,
     MemphisConnectionId,
+    ContentType,
 }
 
 impl MemphisHeaders {
@@ -60,6 +66,7 @@ impl MemphisHeaders {
             Self::MessageId => "msg-id",
             Self::MemphisProducedBy => "$memphis_producedBy",
             Self::MemphisConnectionId => "$memphis_connectionId",
+            Self::ContentType => "content-type",
         }
     }
 }
@@ -72,16 +79,18 @@ impl IntoHeaderName for MemphisHeaders {
 
 pub(crate) enum MemphisSubscriptions {
     DlsPrefix,
-    #[allow(dead_code)]
+    #[cfg(feature = "schemaverse")]
     SchemaUpdatesPrefix,
 }
 
-impl ToString for MemphisSubscriptions {
-    fn to_string(&self) -> String {
-        match self {
-            Self::DlsPrefix => String::from("$memphis_dls_"),
-            Self::SchemaUpdatesPrefix => String::from("$memphis_schema_updates_"),
-        }
+impl Display for MemphisSubscriptions {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let prefix = match self {
+            Self::DlsPrefix => "$memphis_dls_",
+            #[cfg(feature = "schemaverse")]
+            Self::SchemaUpdatesPrefix => "$memphis_schema_updates_",
+        };
+        f.write_str(prefix)
     }
 }
 
@@ -91,10 +100,10 @@ pub enum MemphisNotificationType {
 }
 
 #[cfg(feature = "schemaverse")]
-impl ToString for MemphisNotificationType {
-    fn to_string(&self) -> String {
+impl Display for MemphisNotificationType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
-            Self::SchemaValidationFailAlert => String::from("schema_validation_fail_alert"),
+            Self::SchemaValidationFailAlert => f.write_str("schema_validation_fail_alert"),
         }
     }
 }
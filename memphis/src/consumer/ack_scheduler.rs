@@ -0,0 +1,141 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_nats::jetstream::{AckKind, Message};
+use futures_util::StreamExt;
+use log::{error, trace};
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
+use tokio::sync::watch;
+use tokio_util::time::delay_queue::Key;
+use tokio_util::time::DelayQueue;
+
+use crate::consumer::dedupe::{Deduplicator, MessageKey};
+
+/// Sends progress acks for the outstanding messages of a consumer, shortly before their max ack time
+/// is exceeded. A single task serves all messages of the consumer.
+///
+/// The task stops once the consumer and all of its messages were dropped.
+#[derive(Clone)]
+pub(crate) struct AckScheduler {
+    commands: UnboundedSender<Command>,
+    next_id: Arc<AtomicU64>,
+    dedupe: Arc<Deduplicator>,
+    /// The number of messages which are not settled yet.
+    in_flight: Arc<watch::Sender<usize>>,
+}
+
+enum Command {
+    Track { id: u64, msg: Box<Message> },
+    Settle { id: u64 },
+}
+
+impl AckScheduler {
+    pub(crate) fn new(max_ack_time: Duration, dedupe: Arc<Deduplicator>) -> Self {
+        let (commands, receiver) = unbounded_channel();
+        tokio::spawn(run(receiver, progress_interval(max_ack_time)));
+        Self {
+            commands,
+            next_id: Arc::new(AtomicU64::new(0)),
+            dedupe,
+            in_flight: Arc::new(watch::channel(0).0),
+        }
+    }
+
+    /// Returns a receiver for the number of messages which are not settled yet.
+    pub(crate) fn in_flight(&self) -> watch::Receiver<usize> {
+        self.in_flight.subscribe()
+    }
+
+    /// Sends progress acks for the message until the returned deadline is settled or dropped.
+    /// `received` is the key and generation the message was remembered with by the [Deduplicator].
+    pub(crate) fn track(&self, msg: Message, received: Option<(MessageKey, u64)>) -> AckDeadline {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        self.in_flight.send_modify(|in_flight| *in_flight += 1);
+        let _ = self.commands.send(Command::Track {
+            id,
+            msg: Box::new(msg),
+        });
+        AckDeadline {
+            id,
+            received,
+            scheduler: self.clone(),
+            settled: AtomicBool::new(false),
+        }
+    }
+}
+
+/// The progress acks of a single message, shared by all clones of the message.
+///
+/// Settling or dropping it stops the progress acks and lets the [Deduplicator] forget the message,
+/// so it is received again when Memphis redelivers it.
+pub(crate) struct AckDeadline {
+    id: u64,
+    received: Option<(MessageKey, u64)>,
+    scheduler: AckScheduler,
+    settled: AtomicBool,
+}
+
+impl AckDeadline {
+    /// Stops sending progress acks for the message.
+    pub(crate) fn settle(&self) {
+        if !self.settled.swap(true, Ordering::AcqRel) {
+            if let Some((key, generation)) = &self.received {
+                self.scheduler.dedupe.remove(key, *generation);
+            }
+            let _ = self
+                .scheduler
+                .commands
+                .send(Command::Settle { id: self.id });
+            self.scheduler
+                .in_flight
+                .send_modify(|in_flight| *in_flight -= 1);
+        }
+    }
+}
+
+impl Drop for AckDeadline {
+    fn drop(&mut self) {
+        self.settle();
+    }
+}
+
+/// Progress acks are sent when 90% of the max ack time has passed.
+fn progress_interval(max_ack_time: Duration) -> Duration {
+    let safety_time_ms = max_ack_time.as_millis() as f32 * 0.1f32;
+    max_ack_time.saturating_sub(Duration::from_millis(safety_time_ms.round() as u64))
+}
+
+async fn run(mut commands: UnboundedReceiver<Command>, interval: Duration) {
+    let mut deadlines = DelayQueue::new();
+    let mut tracked: HashMap<u64, (Key, Box<Message>)> = HashMap::new();
+
+    loop {
+        tokio::select! {
+            command = commands.recv() => match command {
+                Some(Command::Track { id, msg }) => {
+                    let deadline = deadlines.insert(id, interval);
+                    tracked.insert(id, (deadline, msg));
+                }
+                Some(Command::Settle { id }) => {
+                    if let Some((deadline, _)) = tracked.remove(&id) {
+                        deadlines.remove(&deadline);
+                    }
+                }
+                None => break,
+            },
+            Some(expired) = deadlines.next(), if !deadlines.is_empty() => {
+                let id = expired.into_inner();
+                if let Some((deadline, msg)) = tracked.get_mut(&id) {
+                    trace!("Sending progress ack");
+                    if let Err(error) = msg.ack_with(AckKind::Progress).await {
+                        error!("Error while sending progress ack: {:?}", error);
+                    }
+                    *deadline = deadlines.insert(id, interval);
+                }
+            }
+        }
+    }
+    trace!("Stopped sending progress acks.");
+}
@@ -1,3 +1,6 @@
+use std::fmt::{Debug, Formatter};
+
+use crate::consumer::MemphisMessage;
 use crate::request_error::RequestError;
 use thiserror::Error;
 
@@ -12,3 +15,23 @@ pub enum ConsumerError {
     #[error("InvalidResponse")]
     InvalidResponse(String),
 }
+
+/// Returned by [fetch](crate::consumer::MemphisConsumer::fetch) if pulling from at least one
+/// partition failed. The messages pulled from the other partitions are not lost,
+/// they still have to be acknowledged.
+#[derive(Error)]
+#[error("FetchError: {error}")]
+pub struct FetchError {
+    pub messages: Vec<MemphisMessage>,
+    #[source]
+    pub error: async_nats::Error,
+}
+
+impl Debug for FetchError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FetchError")
+            .field("messages", &self.messages.len())
+            .field("error", &self.error)
+            .finish()
+    }
+}
@@ -0,0 +1,84 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+/// Identifies a message by the partition it is stored in and its sequence number in that partition.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) struct MessageKey {
+    pub(crate) partition: Option<u32>,
+    pub(crate) stream_sequence: u64,
+}
+
+/// Remembers the messages a consumer has received but not yet settled, so a message which is
+/// delivered again in the meantime is not passed to the application twice.
+///
+/// At most `capacity` messages are remembered, the oldest ones are forgotten first.
+/// A capacity of `0` disables the deduplication.
+pub(crate) struct Deduplicator {
+    capacity: usize,
+    state: Mutex<DedupeState>,
+}
+
+#[derive(Default)]
+struct DedupeState {
+    /// The generation each message was inserted with.
+    received: HashMap<MessageKey, u64>,
+    /// The insertion order. Contains stale entries for messages which were removed or reinserted.
+    order: VecDeque<(MessageKey, u64)>,
+    next_generation: u64,
+}
+
+impl Deduplicator {
+    pub(crate) fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            state: Mutex::default(),
+        }
+    }
+
+    /// Remembers the message. Returns `None` if it was already received,
+    /// otherwise the generation needed to [remove](Deduplicator::remove) it again.
+    pub(crate) fn insert(&self, key: MessageKey) -> Option<u64> {
+        let mut state = self.state();
+        let generation = state.next_generation;
+        state.next_generation += 1;
+
+        if self.capacity == 0 {
+            return Some(generation);
+        }
+        if state.received.contains_key(&key) {
+            return None;
+        }
+
+        state.received.insert(key, generation);
+        state.order.push_back((key, generation));
+
+        while state.received.len() > self.capacity {
+            let Some((oldest, oldest_generation)) = state.order.pop_front() else {
+                break;
+            };
+            if state.received.get(&oldest) == Some(&oldest_generation) {
+                state.received.remove(&oldest);
+            }
+        }
+        if state.order.len() > self.capacity.saturating_mul(2) {
+            let DedupeState {
+                received, order, ..
+            } = &mut *state;
+            order.retain(|(key, generation)| received.get(key) == Some(generation));
+        }
+
+        Some(generation)
+    }
+
+    /// Forgets the message, unless it was received again since it was inserted with `generation`.
+    pub(crate) fn remove(&self, key: &MessageKey, generation: u64) {
+        let mut state = self.state();
+        if state.received.get(key) == Some(&generation) {
+            state.received.remove(key);
+        }
+    }
+
+    fn state(&self) -> MutexGuard<'_, DedupeState> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
@@ -0,0 +1,172 @@
+use async_nats::{HeaderMap, Message};
+use bytes::Bytes;
+use serde::Deserialize;
+
+use crate::constants::memphis_constants::MemphisHeaders;
+#[cfg(feature = "producers")]
+use crate::producer::{ComposableMessage, MemphisProducer, ProducerError};
+#[cfg(feature = "producers")]
+use async_nats::jetstream::context::PublishAckFuture;
+
+/// Why a message was sent to the dead-letter station.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DlsMessageKind {
+    /// The message was not acknowledged within the max message deliveries.
+    Poison,
+    /// The message was rejected by the schema of the station when it was produced.
+    SchemaverseFailure,
+}
+
+/// The producer of a message in the dead-letter station.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct DlsMessageProducer {
+    pub name: String,
+    pub connection_id: String,
+}
+
+/// A message received from the dead-letter station.
+/// See [consume_dls](crate::consumer::MemphisConsumer::consume_dls).
+///
+/// Messages which failed the schema validation arrive in an envelope, which is decoded,
+/// so the payload and headers are always the ones of the original message.
+#[derive(Debug, Clone)]
+pub struct DlsMessage {
+    kind: DlsMessageKind,
+    station_name: Option<String>,
+    payload: Bytes,
+    headers: HeaderMap,
+    producer: Option<DlsMessageProducer>,
+    validation_error: Option<String>,
+    raw: Message,
+}
+
+/// The envelope of a message which failed the schema validation.
+/// Counterpart of the envelope sent by the producer.
+#[derive(Deserialize)]
+struct SchemaverseDlsEnvelope {
+    station_name: String,
+    producer: DlsMessageProducer,
+    message: SchemaverseDlsEnvelopeMessage,
+    validation_error: String,
+}
+
+#[derive(Deserialize)]
+struct SchemaverseDlsEnvelopeMessage {
+    #[serde(default)]
+    headers: HeaderMap,
+    #[serde(deserialize_with = "hex::serde::deserialize")]
+    payload: Vec<u8>,
+}
+
+impl DlsMessage {
+    /// Parses a message of the dead-letter station.
+    pub(crate) fn new(raw: Message) -> Self {
+        if let Ok(envelope) = serde_json::from_slice::<SchemaverseDlsEnvelope>(&raw.payload) {
+            return DlsMessage {
+                kind: DlsMessageKind::SchemaverseFailure,
+                station_name: Some(envelope.station_name),
+                payload: Bytes::from(envelope.message.payload),
+                headers: envelope.message.headers,
+                producer: Some(envelope.producer),
+                validation_error: Some(envelope.validation_error),
+                raw,
+            };
+        }
+
+        let headers = raw.headers.clone().unwrap_or_default();
+        let header = |name: MemphisHeaders| Some(headers.get(name.as_str())?.to_string());
+        let producer = header(MemphisHeaders::MemphisProducedBy).map(|name| DlsMessageProducer {
+            name,
+            connection_id: header(MemphisHeaders::MemphisConnectionId).unwrap_or_default(),
+        });
+
+        DlsMessage {
+            kind: DlsMessageKind::Poison,
+            station_name: None,
+            payload: raw.payload.clone(),
+            headers,
+            producer,
+            validation_error: None,
+            raw,
+        }
+    }
+
+    pub fn kind(&self) -> DlsMessageKind {
+        self.kind
+    }
+
+    /// The station the message was produced to. Only known for schemaverse failures.
+    pub fn station_name(&self) -> Option<&str> {
+        self.station_name.as_deref()
+    }
+
+    /// The payload of the original message.
+    pub fn payload(&self) -> &Bytes {
+        &self.payload
+    }
+
+    /// The headers of the original message.
+    pub fn headers(&self) -> &HeaderMap {
+        &self.headers
+    }
+
+    pub fn producer(&self) -> Option<&DlsMessageProducer> {
+        self.producer.as_ref()
+    }
+
+    /// Why the schema validation failed. Only set for schemaverse failures.
+    pub fn validation_error(&self) -> Option<&str> {
+        self.validation_error.as_deref()
+    }
+
+    /// The message as it was received from the broker.
+    pub fn raw(&self) -> &Message {
+        &self.raw
+    }
+
+    pub fn into_raw(self) -> Message {
+        self.raw
+    }
+
+    /// Produces the original message again, through the given producer.
+    /// The partition is chosen as for any other message, see [produce](MemphisProducer::produce).
+    ///
+    /// The internal Memphis headers and the message id are not copied,
+    /// so the message is neither treated as poison nor dropped as a duplicate.
+    #[cfg(feature = "producers")]
+    pub async fn replay_to_station(
+        &self,
+        producer: &mut MemphisProducer,
+    ) -> Result<PublishAckFuture, ProducerError> {
+        producer.produce(self.to_composable_message()).await
+    }
+
+    /// Produces the original message again to the given partition, through the given producer.
+    ///
+    /// For more details, see [replay_to_station](DlsMessage::replay_to_station).
+    #[cfg(feature = "producers")]
+    pub async fn replay_to_partition(
+        &self,
+        producer: &MemphisProducer,
+        partition: u32,
+    ) -> Result<PublishAckFuture, ProducerError> {
+        producer
+            .produce_to_partition(Some(partition), self.to_composable_message())
+            .await
+    }
+
+    #[cfg(feature = "producers")]
+    fn to_composable_message(&self) -> ComposableMessage {
+        let mut message = ComposableMessage::new().with_payload(self.payload.clone());
+        for (name, values) in self.headers.iter() {
+            let name: &str = name.as_ref();
+            if name.starts_with("$memphis") || name == MemphisHeaders::MessageId.as_str() {
+                continue;
+            }
+            for value in values {
+                message.headers.append(name, value.as_str());
+            }
+        }
+        message
+    }
+}
@@ -5,9 +5,32 @@ use async_nats::Error;
 
 use crate::consumer::MemphisMessage;
 
+/// Lifecycle events of a [MemphisConsumer](crate::consumer::MemphisConsumer).
+/// See [subscribe_events](crate::consumer::MemphisConsumer::subscribe_events).
 #[derive(Clone, Debug)]
+#[allow(clippy::large_enum_variant)]
 pub enum MemphisEvent {
+    /// Not sent on the event channel, messages are delivered by [consume](crate::consumer::MemphisConsumer::consume).
     MessageReceived(MemphisMessage),
+    /// The stream of the station (or one of its partitions) could not be found while pinging the consumer.
     StationUnavailable(Arc<GetStreamError>),
+    /// Pinging the consumer failed, e.g. because JetStream does not know the consumer.
     ConsumerUnavailable(Arc<Error>),
+    /// Receiving messages from the pull subscription of a partition failed.
+    PullStreamError {
+        partition: Option<u32>,
+        error: Arc<Error>,
+    },
+    /// The pull subscription of a partition could not be started.
+    SubscriptionFailed {
+        partition: Option<u32>,
+        error: Arc<Error>,
+    },
+    /// The consumer was stopped, either by the application or after a subscription failed.
+    ConsumerStopped,
+    /// The consumer was lost and has been recovered.
+    /// See [ConsumerRecoveryOptions](crate::consumer::ConsumerRecoveryOptions).
+    ConsumerRecovered,
+    /// The consumer was lost and could not be recovered, it is stopped.
+    RecoveryFailed,
 }
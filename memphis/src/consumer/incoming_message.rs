@@ -1,4 +1,3 @@
-use std::collections::HashSet;
 use std::fmt::{Debug, Formatter};
 use std::string::FromUtf8Error;
 use std::sync::Arc;
@@ -6,24 +5,24 @@ use std::time::Duration;
 
 use async_nats::jetstream::{AckKind, Message};
 use async_nats::HeaderMap;
-use log::{error, info};
-use tokio::sync::RwLock;
-use tokio::task::AbortHandle;
-use tokio::time::Instant;
+use log::error;
+use serde::de::DeserializeOwned;
 
-use crate::constants::memphis_constants::MemphisSpecialStation;
+use crate::codec::{decode_with_content_type, Codec, CodecError};
+use crate::constants::memphis_constants::{MemphisHeaders, MemphisSpecialStation};
+use crate::consumer::ack_scheduler::{AckDeadline, AckScheduler};
+use crate::consumer::dedupe::MessageKey;
+use crate::consumer::message_metadata::partition_of_stream;
+use crate::consumer::{MessageError, MessageMetadata};
 use crate::memphis_client::MemphisClient;
 use crate::models::request::pm_ack_msg::PmAckMsg;
-use crate::RequestError;
 
 #[derive(Clone)]
 pub struct MemphisMessage {
     msg: Message,
     memphis_client: MemphisClient,
     consumer_group: String,
-    known_messages: Arc<RwLock<HashSet<String>>>,
-    known_message_key: String,
-    abort_handle: Arc<AbortHandle>,
+    ack_deadline: Arc<AckDeadline>,
     pub max_ack_time: Duration,
 }
 
@@ -33,38 +32,24 @@ impl MemphisMessage {
         memphis_client: MemphisClient,
         consumer_group: String,
         max_ack_time: Duration,
-        known_message_key: String,
-        known_messages: Arc<RwLock<HashSet<String>>>,
+        received: Option<(MessageKey, u64)>,
+        ack_scheduler: &AckScheduler,
     ) -> Self {
-        let msg_clone = msg.clone();
-        let abort_handle = tokio::spawn(async move {
-            loop {
-                let safety_time_ms = max_ack_time.as_millis() as f32 * 0.1f32;
-                let safety_time = Duration::from_millis(safety_time_ms.round() as u64);
-                tokio::time::sleep_until(Instant::now() + max_ack_time - safety_time).await;
-                info!("Sending progress ack");
-                if let Err(error) = msg_clone.clone().ack_with(AckKind::Progress).await {
-                    error!("Error while sending progress ack: {:?}", error);
-                }
-            }
-        })
-        .abort_handle();
+        let ack_deadline = ack_scheduler.track(msg.clone(), received);
         MemphisMessage {
             msg,
             memphis_client,
             consumer_group,
             max_ack_time,
-            known_messages,
-            known_message_key,
-            abort_handle: Arc::new(abort_handle),
+            ack_deadline: Arc::new(ack_deadline),
         }
     }
 
     /// Acknowledges the message. Causes the message to be marked as processed and removed from the queue.
-    pub async fn ack(&self) -> Result<(), RequestError> {
+    pub async fn ack(&self) -> Result<(), MessageError> {
         self.disable_missed_ack_safety().await;
         let res = self.msg.ack().await;
-        return match res {
+        match res {
             Ok(_) => Ok(()),
             Err(e) => {
                 error!("Error while acking message: {:?}", e);
@@ -82,9 +67,29 @@ impl MemphisMessage {
                         }
                     }
                 }
-                Err(e.into())
+                Err(MessageError::AckError(e))
             }
-        };
+        }
+    }
+
+    /// Negatively acknowledges the message, so it is redelivered immediately.
+    pub async fn nack(&self) -> Result<(), MessageError> {
+        self.disable_missed_ack_safety().await;
+        self.ack_with(AckKind::Nak(None)).await
+    }
+
+    /// Terminates the message, so it is not redelivered. It is not sent to the DLS.
+    pub async fn term(&self) -> Result<(), MessageError> {
+        self.disable_missed_ack_safety().await;
+        self.ack_with(AckKind::Term).await
+    }
+
+    /// Tells the broker that the message is still being processed, which resets the max ack time.
+    ///
+    /// A progress acknowledgement is sent automatically shortly before the max ack time is reached,
+    /// unless [disable_missed_ack_safety](MemphisMessage::disable_missed_ack_safety) was called.
+    pub async fn in_progress(&self) -> Result<(), MessageError> {
+        self.ack_with(AckKind::Progress).await
     }
 
     /// Get the payload of the underlying NATS message.
@@ -96,57 +101,87 @@ impl MemphisMessage {
         String::from_utf8(self.msg.payload.to_vec())
     }
 
+    /// Returns how often the message was delivered, including this delivery.
+    pub fn get_delivery_count(&self) -> u64 {
+        self.msg
+            .info()
+            .map(|info| info.delivered.max(1) as u64)
+            .unwrap_or(1)
+    }
+
+    /// Returns the sequence numbers, delivery information and producer of the message.
+    pub fn metadata(&self) -> Result<MessageMetadata, MessageError> {
+        let info = self.msg.info().map_err(MessageError::MetadataError)?;
+        let header = |name: MemphisHeaders| {
+            self.msg
+                .headers
+                .as_ref()?
+                .get(name.as_str())
+                .map(|value| value.to_string())
+        };
+
+        Ok(MessageMetadata {
+            stream_sequence: info.stream_sequence,
+            consumer_sequence: info.consumer_sequence,
+            delivery_count: info.delivered.max(1) as u64,
+            published: info.published.into(),
+            pending: info.pending,
+            partition: partition_of_stream(info.stream),
+            producer_name: header(MemphisHeaders::MemphisProducedBy),
+            connection_id: header(MemphisHeaders::MemphisConnectionId),
+        })
+    }
+
     /// Get the headers of the underlying NATS message.
     pub fn get_headers(&self) -> &Option<HeaderMap> {
         &self.msg.headers
     }
 
+    /// Returns the value of the `content-type` header, if the message has one.
+    pub fn get_content_type(&self) -> Option<&str> {
+        self.msg
+            .headers
+            .as_ref()?
+            .get(MemphisHeaders::ContentType.as_str())
+            .map(|value| value.as_str())
+    }
+
+    /// Deserializes the payload with the codec matching the `content-type` header of the message.
+    /// Messages without a content type are expected to be JSON.
+    pub fn deserialize<T: DeserializeOwned>(&self) -> Result<T, CodecError> {
+        decode_with_content_type(self.get_content_type(), &self.msg.payload)
+    }
+
+    /// Deserializes the payload with the given codec, ignoring the `content-type` header.
+    pub fn deserialize_with<T: DeserializeOwned, C: Codec>(
+        &self,
+        codec: &C,
+    ) -> Result<T, CodecError> {
+        codec.decode(&self.msg.payload)
+    }
+
     /// Delay the message for the specified duration.
     ///
     /// # Arguments
     ///
     /// * `delay` - The duration to delay the message.
-    pub async fn delay(&self, delay: Duration) -> Result<(), ()> {
+    pub async fn delay(&self, delay: Duration) -> Result<(), MessageError> {
         self.disable_missed_ack_safety().await;
-        if let Some(headers) = self.get_headers() {
-            if let Some(_msg_id) = headers.get("$memphis_pm_id") {
-                return match self.msg.ack_with(AckKind::Nak(Some(delay))).await {
-                    Ok(_) => Ok(()),
-                    Err(_) => Err(()),
-                };
-            } else if let Some(_cg_name) = headers.get("$memphis_pm_cg_name") {
-                return match self.msg.ack_with(AckKind::Nak(Some(delay))).await {
-                    Ok(_) => Ok(()),
-                    Err(_) => Err(()),
-                };
-            }
-        }
-        match self.msg.ack_with(AckKind::Nak(Some(delay))).await {
-            Ok(_) => Ok(()),
-            Err(_) => Err(()),
-        }
+        self.ack_with(AckKind::Nak(Some(delay))).await
+    }
+
+    async fn ack_with(&self, kind: AckKind) -> Result<(), MessageError> {
+        self.msg.ack_with(kind).await.map_err(|e| {
+            error!("Error while acking message with {:?}: {:?}", kind, e);
+            MessageError::AckError(e)
+        })
     }
 
     /// This function is used to disable the safety mechanism that sends a progress ack to the server
     /// if the message is not acked within the specified time.
     /// This also removes the message from the list of known messages, which have been received but not acked.
     pub async fn disable_missed_ack_safety(&self) {
-        self.abort_handle.abort();
-        self.known_messages
-            .write()
-            .await
-            .remove(&self.known_message_key);
-    }
-}
-
-impl Drop for MemphisMessage {
-    fn drop(&mut self) {
-        self.abort_handle.abort();
-        let known_messages = self.known_messages.clone();
-        let key = self.known_message_key.clone();
-        tokio::spawn(async move {
-            known_messages.write().await.remove(&key);
-        });
+        self.ack_deadline.settle();
     }
 }
 
@@ -1,32 +1,66 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
 use std::time::Duration;
 
+use async_nats::jetstream;
 use async_nats::jetstream::consumer::PullConsumer;
+use async_nats::jetstream::response::Response;
+use async_nats::jetstream::ErrorCode;
 
-use async_nats::{Error, Message};
+use async_nats::{Client, Error};
 use futures_util::StreamExt;
 use log::{debug, error, info, trace, warn};
-use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
+use tokio::sync::broadcast;
+use tokio::sync::mpsc::{channel, unbounded_channel, UnboundedReceiver};
+use tokio::sync::{watch, Notify, RwLock, Semaphore};
 use tokio_util::sync::CancellationToken;
 
 use crate::constants::memphis_constants::{MemphisSpecialStation, MemphisSubscriptions};
-use crate::consumer::consumer_error::ConsumerError;
-use crate::consumer::memphis_consumer_options::MemphisConsumerOptions;
-use crate::consumer::MemphisMessage;
+use crate::consumer::ack_scheduler::AckScheduler;
+use crate::consumer::consumer_error::{ConsumerError, FetchError};
+use crate::consumer::dedupe::{Deduplicator, MessageKey};
+use crate::consumer::memphis_consumer_options::{ConsumerRecoveryOptions, MemphisConsumerOptions};
+use crate::consumer::message_handler::retry_backoff;
+use crate::consumer::{
+    DlsMessage, MemphisEvent, MemphisMessage, MemphisMessageStream, MessageHandler, MessageSender,
+    Nack,
+};
 use crate::helper::memphis_util::{get_internal_name, sanitize_name};
-use crate::memphis_client::MemphisClient;
+use crate::memphis_client::{send_request, MemphisClient, RegistryEntry, Resource};
 use crate::models::request::CreateConsumerRequest;
 use crate::models::request::DestroyConsumerRequest;
 use crate::models::response::CreateConsumerResponse;
 use crate::station::MemphisStation;
 use crate::RequestError;
 
+/// The number of events kept for subscribers which are lagging behind.
+const EVENT_CHANNEL_CAPACITY: usize = 64;
+
 /// The MemphisConsumer is used to consume messages from a Memphis Station.
 /// See [MemphisStation::create_consumer] for more information.
 pub struct MemphisConsumer {
     station: MemphisStation,
     options: MemphisConsumerOptions,
     cancellation_token: CancellationToken,
-    partitions_list: Option<Vec<u32>>,
+    partitions_list: Arc<RwLock<Option<Vec<u32>>>>,
+    events: broadcast::Sender<MemphisEvent>,
+    subscriptions: Arc<Mutex<Subscriptions>>,
+    ack_scheduler: AckScheduler,
+    dedupe: Arc<Deduplicator>,
+    /// The partition the next [fetch](MemphisConsumer::fetch) starts at.
+    fetch_cursor: Arc<AtomicUsize>,
+    registration: Arc<ConsumerRegistration>,
+    /// The entry in the resource registry of the client, see [shutdown](MemphisClient::shutdown).
+    /// `None` for the handles of background tasks, which must not stop or deregister the consumer
+    /// when dropped.
+    registry_entry: Option<RegistryEntry>,
+}
+
+/// The receivers of the pull subscriptions, so they can be restarted when the consumer is recovered.
+struct Subscriptions {
+    /// Cancels the current pull subscriptions. Child of the cancellation token of the consumer.
+    token: CancellationToken,
+    senders: Vec<MessageSender>,
 }
 
 impl MemphisConsumer {
@@ -47,6 +81,18 @@ impl MemphisConsumer {
             return Err(ConsumerError::InvalidSequence);
         }
 
+        let cancellation_token = CancellationToken::new();
+        let (events, _) = broadcast::channel(EVENT_CHANNEL_CAPACITY);
+        let subscriptions = Subscriptions {
+            token: cancellation_token.child_token(),
+            senders: Vec::new(),
+        };
+
+        let dedupe = Arc::new(Deduplicator::new(options.dedupe_capacity));
+        let ack_scheduler = AckScheduler::new(options.max_ack_time, dedupe.clone());
+        let partitions_list = Arc::new(RwLock::new(None));
+        let subscriptions = Arc::new(Mutex::new(subscriptions));
+
         let create_consumer_request = CreateConsumerRequest {
             consumer_name: &options.consumer_name,
             station_name: &station.options.station_name,
@@ -60,74 +106,94 @@ impl MemphisConsumer {
             req_version: 2,
             username: &station.memphis_client.username,
         };
+        let registration = Arc::new(ConsumerRegistration {
+            consumer_name: options.consumer_name.clone(),
+            station_name: station.options.station_name.clone(),
+            create_request: serde_json::to_value(&create_consumer_request)
+                .map_err(RequestError::from)?,
+            cancellation_token: cancellation_token.clone(),
+            subscriptions: subscriptions.clone(),
+            events: events.clone(),
+            in_flight: ack_scheduler.in_flight(),
+            partitions_list: partitions_list.clone(),
+            partitions_changed: Notify::new(),
+        });
+
+        let mut consumer = Self {
+            station,
+            options,
+            cancellation_token,
+            partitions_list,
+            events,
+            subscriptions,
+            ack_scheduler,
+            dedupe,
+            fetch_cursor: Arc::new(AtomicUsize::new(0)),
+            registration,
+            registry_entry: None,
+        };
+
+        let partitions_list = consumer.register().await?;
+        *consumer.partitions_list.write().await = partitions_list;
+        consumer.registry_entry = Some(
+            consumer
+                .station
+                .memphis_client
+                .registry
+                .register(consumer.registration.clone()),
+        );
+
+        info!("Consumer '{}' created successfully", &consumer.get_name());
+
+        consumer.ping_consumer().await;
+
+        Ok(consumer)
+    }
 
-        let res = match station
+    /// Creates the consumer in Memphis. Returns the partitions of the station,
+    /// or `None` if the broker does not support partitions.
+    async fn register(&self) -> Result<Option<Vec<u32>>, ConsumerError> {
+        let res = match self
+            .station
             .memphis_client
             .send_internal_request(
-                &create_consumer_request,
+                &self.registration.create_request,
                 MemphisSpecialStation::ConsumerCreations,
             )
             .await
         {
             Ok(res) => res,
             Err(e) => {
-                error!("Error creating consumer: {}", e.to_string());
+                error!("Error creating consumer: {}", e);
                 return Err(e.into());
             }
         };
 
         let res = std::str::from_utf8(&res.payload)
-            .map_err(|e| RequestError::MemphisError(e.to_string()))?;
+            .map_err(|e| RequestError::InvalidResponse(e.to_string()))?;
 
-        let cancellation_token = CancellationToken::new();
-
-        let consumer = match serde_json::from_str::<CreateConsumerResponse>(res) {
-            Ok(x) => Self {
-                station,
-                options,
-                cancellation_token,
-                partitions_list: Some(x.partitions_update.partitions_list),
-            },
+        match serde_json::from_str::<CreateConsumerResponse>(res) {
+            Ok(x) => Ok(Some(x.partitions_update.partitions_list)),
+            Err(_) if res.is_empty() => Ok(None),
             Err(e) => {
-                if res.is_empty() {
-                    Self {
-                        station,
-                        options,
-                        cancellation_token,
-                        partitions_list: None,
-                    }
-                } else {
-                    error!("Error creating consumer: {}", e.to_string());
-                    return Err(ConsumerError::InvalidResponse(res.to_string()));
-                }
+                error!("Error creating consumer: {}", e);
+                Err(ConsumerError::InvalidResponse(res.to_string()))
             }
-        };
-
-        info!("Consumer '{}' created successfully", &consumer.get_name());
-
-        consumer.ping_consumer().await;
-
-        Ok(consumer)
+        }
     }
 
     async fn start_pull_subscription(
         &self,
         partition: Option<u32>,
-        sender: UnboundedSender<MemphisMessage>,
+        sender: MessageSender,
+        cancellation_token: CancellationToken,
     ) -> Result<(), Error> {
         debug!(
             "Starting pull subscription for consumer '{}', Partition: {:?}",
             &self.get_name(),
             partition
         );
-        let consumer: PullConsumer = self
-            .station
-            .memphis_client
-            .get_jetstream_context()
-            .get_stream(&self.station.get_internal_name(partition))
-            .await?
-            .get_consumer(&self.get_internal_name())
-            .await?;
+        let consumer = self.get_pull_consumer(partition).await?;
 
         let x = consumer
             .stream()
@@ -147,11 +213,11 @@ impl MemphisConsumer {
             }
         };
 
-        let client_clone = self.station.memphis_client.clone();
+        let consumer = self.handle();
         let options_clone = self.options.clone();
 
-        let cancellation_token_clone = self.cancellation_token.clone();
-        let known_messages = self.station.known_messages.clone();
+        let cancellation_token_clone = cancellation_token;
+        let events = self.events.clone();
 
         tokio::spawn(async move {
             trace!(
@@ -159,49 +225,31 @@ impl MemphisConsumer {
                 &options_clone.consumer_name,
                 &options_clone.consumer_group
             );
-            loop {
+            'pull: loop {
                 tokio::select! {
                     msg = stream.next() => {
                         if let Some(msg) = msg {
                             if let Ok(msg) = msg {
-                                let sequence = match msg.info() {
-                                    Ok(info) => info.stream_sequence,
-                                    Err(_e) => {
-                                        error!("Error while getting message info.");
-                                        0
-                                    },
-                                };
-
-                                let subject = &msg.subject;
-                                let known_message_key = format!("{}-{}", subject, sequence);
-
-                                if known_messages.read().await.contains(&known_message_key) {
-                                    trace!("Message was already received (Subject: {}, Sequence: {})", subject, sequence);
+                                let Some(memphis_message) = consumer.receive_message(msg, partition) else {
                                     continue;
-                                }
+                                };
 
-                                trace!(
-                                    "Message received from Memphis. (Subject: {}, Sequence: {})",
-                                    subject,
-                                    sequence
-                                );
-
-                                known_messages.write().await.insert(known_message_key.clone());
-
-                                let memphis_message = MemphisMessage::new(
-                                    msg,
-                                    client_clone.clone(),
-                                    options_clone.consumer_group.clone(),
-                                    options_clone.max_ack_time,
-                                    known_message_key,
-                                    known_messages.clone(),
-                                );
-
-                                if let Err(e) = sender.send(memphis_message) {
-                                    error!("Error while sending message to the receiver. {:?}", e);
+                                // Waits while a bounded receiver is full, so no more messages are pulled.
+                                tokio::select! {
+                                    res = sender.send(memphis_message) => {
+                                        if let Err(e) = res {
+                                            error!("Error while sending message to the receiver, stopping consumer. {:?}", e);
+                                            break 'pull;
+                                        }
+                                    }
+                                    _ = cancellation_token_clone.cancelled() => break 'pull,
                                 }
                             } else if let Err(e) = msg {
                                 error!("Error while receiving messages from Stream. {}", e);
+                                let _ = events.send(MemphisEvent::PullStreamError {
+                                    partition,
+                                    error: Arc::new(e.into()),
+                                });
                             }
                         } else {
                             trace!("Consumer '{}' on group '{}' received None message", &options_clone.consumer_name, &options_clone.consumer_group);
@@ -218,6 +266,163 @@ impl MemphisConsumer {
         Ok(())
     }
 
+    async fn get_pull_consumer(&self, partition: Option<u32>) -> Result<PullConsumer, Error> {
+        self.station
+            .memphis_client
+            .get_jetstream_context()
+            .get_stream(&self.station.get_internal_name(partition))
+            .await?
+            .get_consumer(&self.get_internal_name())
+            .await
+    }
+
+    /// Wraps a message pulled from the given partition.
+    /// Returns `None` if the message was already received and is not settled yet.
+    fn receive_message(
+        &self,
+        msg: jetstream::Message,
+        partition: Option<u32>,
+    ) -> Option<MemphisMessage> {
+        let received = match msg.info() {
+            Ok(info) => {
+                let key = MessageKey {
+                    partition,
+                    stream_sequence: info.stream_sequence,
+                };
+                match self.dedupe.insert(key) {
+                    Some(generation) => Some((key, generation)),
+                    None => {
+                        trace!(
+                            "Message was already received (Subject: {}, Sequence: {})",
+                            &msg.subject,
+                            info.stream_sequence
+                        );
+                        return None;
+                    }
+                }
+            }
+            Err(_e) => {
+                error!("Error while getting message info.");
+                None
+            }
+        };
+
+        trace!(
+            "Message received from Memphis. (Subject: {}, Key: {:?})",
+            &msg.subject,
+            received
+        );
+
+        Some(MemphisMessage::new(
+            msg,
+            self.station.memphis_client.clone(),
+            self.options.consumer_group.clone(),
+            self.options.max_ack_time,
+            received,
+            &self.ack_scheduler,
+        ))
+    }
+
+    /// # Fetches a single batch of messages from Memphis.
+    /// Pulls at most `max_messages` messages, spread over all partitions of the station, and returns
+    /// once they were received or the `timeout` has expired. Unlike [consume](MemphisConsumer::consume),
+    /// no background task is started.
+    ///
+    /// If `max_messages` is smaller than the number of partitions, every call starts at the partition
+    /// after the last one fetched from, so all partitions are read eventually.
+    /// If pulling from a partition fails, the [FetchError] holds the messages of the other partitions.
+    ///
+    /// # Example
+    /// ```rust
+    /// use std::time::Duration;
+    /// use memphis_rust_community::memphis_client::MemphisClient;
+    /// use memphis_rust_community::consumer::MemphisConsumerOptions;
+    /// use memphis_rust_community::station::MemphisStationsOptions;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let client = MemphisClient::new("localhost:6666", "root", "memphis", None).await.unwrap();
+    ///
+    ///     let station_options = MemphisStationsOptions::new("test_station");
+    ///     let station = client.create_station(station_options).await.unwrap();
+    ///
+    ///     let consumer_options = MemphisConsumerOptions::new("test_consumer");
+    ///     let consumer = station.create_consumer(consumer_options).await.unwrap();
+    ///
+    ///     let messages = consumer.fetch(100, Duration::from_secs(5)).await.unwrap();
+    ///     for msg in messages {
+    ///         // Do something with the message
+    ///         msg.ack().await.unwrap();
+    ///     }
+    /// }
+    /// ```
+    pub async fn fetch(
+        &self,
+        max_messages: usize,
+        timeout: Duration,
+    ) -> Result<Vec<MemphisMessage>, FetchError> {
+        let partitions = partitions(&*self.partitions_list.read().await);
+        if partitions.is_empty() || max_messages == 0 {
+            return Ok(Vec::new());
+        }
+
+        let share = max_messages / partitions.len();
+        let remainder = max_messages % partitions.len();
+        let start = self.fetch_cursor.fetch_add(remainder, Ordering::Relaxed) % partitions.len();
+
+        let batches = partitions
+            .iter()
+            .cycle()
+            .skip(start)
+            .take(partitions.len())
+            .enumerate()
+            .map(|(i, partition)| (*partition, share + usize::from(i < remainder)))
+            .filter(|(_, max_messages)| *max_messages > 0)
+            .map(|(partition, max_messages)| async move {
+                let mut messages = Vec::with_capacity(max_messages);
+                let mut batch = match self.get_pull_consumer(partition).await {
+                    Ok(consumer) => match consumer
+                        .batch()
+                        .max_messages(max_messages)
+                        .expires(timeout)
+                        .messages()
+                        .await
+                    {
+                        Ok(batch) => batch,
+                        Err(e) => return (messages, Some(e.into())),
+                    },
+                    Err(e) => return (messages, Some(e)),
+                };
+
+                while let Some(msg) = batch.next().await {
+                    match msg {
+                        Ok(msg) => {
+                            if let Some(msg) = self.receive_message(msg, partition) {
+                                messages.push(msg);
+                            }
+                        }
+                        Err(e) => return (messages, Some(e)),
+                    }
+                }
+                (messages, None::<Error>)
+            });
+
+        let mut messages = Vec::new();
+        let mut error = None;
+        for (batch, batch_error) in futures_util::future::join_all(batches).await {
+            messages.extend(batch);
+            if let Some(e) = batch_error {
+                error!("Error while fetching messages. {}", e);
+                error.get_or_insert(e);
+            }
+        }
+
+        match error {
+            None => Ok(messages),
+            Some(error) => Err(FetchError { messages, error }),
+        }
+    }
+
     /// # Starts consuming messages from Memphis.
     /// This method will spawn a new Tokio task that will start to consume messages from Memphis.
     ///
@@ -255,28 +460,93 @@ impl MemphisConsumer {
     /// ```
     pub async fn consume(&self) -> Result<UnboundedReceiver<MemphisMessage>, Error> {
         let (sender, receiver) = unbounded_channel::<MemphisMessage>();
-        let cloned_token = self.cancellation_token.clone();
-        let cloned_partitions_list = self.partitions_list.clone();
+        self.start_pull_subscriptions(MessageSender::Unbounded(sender))
+            .await?;
+        Ok(receiver)
+    }
+
+    /// # Starts consuming messages from Memphis as a [Stream](futures_util::Stream).
+    /// Unlike [consume](MemphisConsumer::consume), at most `batch_size` messages are buffered.
+    /// When the application falls behind, the consumer stops fetching messages until it catches up.
+    ///
+    /// # Example
+    /// ```rust
+    /// use futures_util::StreamExt;
+    /// use memphis_rust_community::memphis_client::MemphisClient;
+    /// use memphis_rust_community::consumer::MemphisConsumerOptions;
+    /// use memphis_rust_community::station::MemphisStationsOptions;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let client = MemphisClient::new("localhost:6666", "root", "memphis", None).await.unwrap();
+    ///
+    ///     let station_options = MemphisStationsOptions::new("test_station");
+    ///     let station = client.create_station(station_options).await.unwrap();
+    ///
+    ///     let consumer_options = MemphisConsumerOptions::new("test_consumer")
+    ///         .with_generate_unique_suffix(true);
+    ///     let consumer = station.create_consumer(consumer_options).await.unwrap();
+    ///
+    ///     let mut messages = consumer.consume_stream().await.unwrap();
+    ///     while let Some(msg) = messages.next().await {
+    ///         // Do something with the message
+    ///         msg.ack().await.unwrap();
+    ///     }
+    /// }
+    /// ```
+    pub async fn consume_stream(&self) -> Result<MemphisMessageStream, Error> {
+        let (sender, receiver) = channel::<MemphisMessage>(self.options.batch_size.max(1));
+        self.start_pull_subscriptions(MessageSender::Bounded(sender))
+            .await?;
+        Ok(MemphisMessageStream::new(receiver))
+    }
+
+    async fn start_pull_subscriptions(&self, sender: MessageSender) -> Result<(), Error> {
+        let cancellation_token = {
+            let mut subscriptions = self.subscriptions();
+            subscriptions.senders.push(sender.clone());
+            subscriptions.token.clone()
+        };
+        let cloned_partitions_list = self.partitions_list.read().await.clone();
 
         match cloned_partitions_list {
             None => {
-                self.start_pull_subscription(None, sender.clone()).await?;
+                if let Err(e) = self
+                    .start_pull_subscription(None, sender, cancellation_token)
+                    .await
+                {
+                    let error = Arc::new(e);
+                    let _ = self.events.send(MemphisEvent::SubscriptionFailed {
+                        partition: None,
+                        error: error.clone(),
+                    });
+                    return Err(error.to_string().into());
+                }
             }
             Some(list) => {
                 for x in list {
-                    let res = self.start_pull_subscription(Some(x), sender.clone()).await;
+                    let res = self
+                        .start_pull_subscription(
+                            Some(x),
+                            sender.clone(),
+                            cancellation_token.clone(),
+                        )
+                        .await;
                     if let Err(e) = res {
                         error!(
                             "Error while starting pull subscription. Stopping consumer. {}",
                             e
                         );
-                        cloned_token.cancel();
+                        let _ = self.events.send(MemphisEvent::SubscriptionFailed {
+                            partition: Some(x),
+                            error: Arc::new(e),
+                        });
+                        self.stop();
                     }
                 }
             }
         }
-        drop(sender);
-        Ok(receiver)
+        Ok(())
     }
 
     /// # Starts consuming DLS messages from Memphis.
@@ -290,12 +560,12 @@ impl MemphisConsumer {
     /// This is to ensure that messages are not duplicated.
     ///
     /// # Returns
-    /// A Receiver that will receive the DLS messages.
-    pub async fn consume_dls(&self) -> Result<UnboundedReceiver<Message>, Error> {
-        let (s, r) = unbounded_channel::<Message>();
+    /// A Receiver that will receive the DLS messages, see [DlsMessage].
+    pub async fn consume_dls(&self) -> Result<UnboundedReceiver<DlsMessage>, Error> {
+        let (s, r) = unbounded_channel::<DlsMessage>();
         let subject = format!(
             "{}{}_{}",
-            MemphisSubscriptions::DlsPrefix.to_string(),
+            MemphisSubscriptions::DlsPrefix,
             &self.station.get_internal_name(None),
             &self.get_internal_name()
         );
@@ -313,7 +583,7 @@ impl MemphisConsumer {
             loop {
                 tokio::select! {
                     Some(message) = dls_sub.next() => {
-                        if let Err(e) = s.send(message) {
+                        if let Err(e) = s.send(DlsMessage::new(message)) {
                             error!("Error while sending DLS message to the channel. {}", e);
                         }
                     },
@@ -329,37 +599,127 @@ impl MemphisConsumer {
         Ok(r)
     }
 
+    /// # Processes messages with the given handler until the consumer is stopped.
+    /// Up to `concurrency` messages are handled at the same time.
+    ///
+    /// A message is acknowledged if the handler returns [Ack](crate::consumer::Ack).
+    /// If it returns a [Nack], the message is delayed or left to be redelivered by Memphis.
+    /// Once [stop](MemphisConsumer::stop) is called, no new messages are handled and this method
+    /// returns after the running handlers have finished.
+    ///
+    /// # Example
+    /// ```rust
+    /// use memphis_rust_community::memphis_client::MemphisClient;
+    /// use memphis_rust_community::consumer::{Ack, MemphisConsumerOptions, MemphisMessage, Nack};
+    /// use memphis_rust_community::station::MemphisStationsOptions;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let client = MemphisClient::new("localhost:6666", "root", "memphis", None).await.unwrap();
+    ///
+    ///     let station_options = MemphisStationsOptions::new("test_station");
+    ///     let station = client.create_station(station_options).await.unwrap();
+    ///
+    ///     let consumer_options = MemphisConsumerOptions::new("test_consumer");
+    ///     let consumer = station.create_consumer(consumer_options).await.unwrap();
+    ///
+    ///     consumer
+    ///         .run(
+    ///             |msg: MemphisMessage| async move {
+    ///                 match msg.get_data_as_string() {
+    ///                     Ok(_data) => Ok(Ack),
+    ///                     Err(_) => Err(Nack::Reject),
+    ///                 }
+    ///             },
+    ///             10,
+    ///         )
+    ///         .await
+    ///         .unwrap();
+    /// }
+    /// ```
+    pub async fn run(&self, handler: impl MessageHandler, concurrency: usize) -> Result<(), Error> {
+        // Waiting for the handlers acquires all permits at once, which takes a `u32`.
+        let concurrency =
+            concurrency.clamp(1, Semaphore::MAX_PERMITS.min(u32::MAX as usize)) as u32;
+        let semaphore = Arc::new(Semaphore::new(concurrency as usize));
+        let handler = Arc::new(handler);
+        let mut messages = self.consume_stream().await?;
+
+        loop {
+            let permit = tokio::select! {
+                permit = semaphore.clone().acquire_owned() => permit?,
+                _ = self.cancellation_token.cancelled() => break,
+            };
+            let message = tokio::select! {
+                Some(message) = messages.next() => message,
+                _ = self.cancellation_token.cancelled() => break,
+                else => break,
+            };
+
+            let handler = handler.clone();
+            tokio::spawn(async move {
+                let result = handler.handle(message.clone()).await;
+                match result {
+                    Ok(_) => {
+                        if let Err(e) = message.ack().await {
+                            error!("Error while acknowledging handled message. {}", e);
+                        }
+                    }
+                    Err(Nack::Retry) => {
+                        let delay = retry_backoff(message.get_delivery_count());
+                        if let Err(e) = message.delay(delay).await {
+                            error!("Error while delaying message for {:?}. {}", delay, e);
+                        }
+                    }
+                    Err(Nack::RetryAfter(delay)) => {
+                        if let Err(e) = message.delay(delay).await {
+                            error!("Error while delaying message for {:?}. {}", delay, e);
+                        }
+                    }
+                    Err(Nack::Reject) => {
+                        debug!("Message was rejected by the handler.");
+                    }
+                }
+                drop(permit);
+            });
+        }
+
+        messages.close();
+        // Wait for the running handlers to finish.
+        let _ = semaphore.acquire_many(concurrency).await?;
+        debug!(
+            "Consumer '{}' stopped running the message handler.",
+            &self.options.consumer_name
+        );
+        Ok(())
+    }
+
+    /// Returns a receiver for the lifecycle events of this consumer, like failing pings or pull subscriptions.
+    /// Only events which occur after subscribing are received.
+    pub fn subscribe_events(&self) -> broadcast::Receiver<MemphisEvent> {
+        self.events.subscribe()
+    }
+
     /// This will stop the consumer, but not destroy it on the server.
-    pub fn stop(self) {
-        self.cancellation_token.cancel();
+    /// Consumers which were not destroyed are destroyed by [shutdown](MemphisClient::shutdown).
+    pub fn stop(&self) {
+        self.registration.stop();
     }
 
     /// Sends a request to destroy/delete this Consumer.
     pub async fn destroy(self) -> Result<(), ConsumerError> {
-        self.cancellation_token.cancel();
-        let destroy_request = DestroyConsumerRequest {
-            consumer_name: &self.options.consumer_name,
-            station_name: &self.station.options.station_name,
-            username: &self.station.memphis_client.username,
-            connection_id: &self.station.memphis_client.connection_id,
-            req_version: 1,
-        };
-
+        self.stop();
         if let Err(e) = self
-            .station
-            .memphis_client
-            .send_internal_request(
-                &destroy_request,
-                MemphisSpecialStation::ConsumerDestructions,
-            )
+            .registration
+            .destroy(&self.station.memphis_client)
             .await
         {
-            error!("Error destroying consumer. {}", &e);
+            // Stays registered, so it is destroyed again on shutdown.
+            if let Some(registry_entry) = &self.registry_entry {
+                registry_entry.keep();
+            }
             return Err(e.into());
         }
-
-        info!("Destroyed consumer {}.", &self.options.consumer_name);
-
         Ok(())
     }
 
@@ -368,72 +728,42 @@ impl MemphisConsumer {
     }
 
     /// Starts pinging the consumer, to ensure its availability.
+    /// If the server no longer knows the consumer and recovery is enabled, it is recovered.
+    /// Pings are skipped while the client is disconnected.
+    ///
+    /// The same task restarts the pull subscriptions when the partitions changed while the
+    /// consumer was registered again after a reconnect.
     async fn ping_consumer(&self) {
-        let cloned_token = self.cancellation_token.clone();
-        let cloned_client = self.station.memphis_client.clone();
-        let cloned_partitions_data = self.partitions_list.clone();
-        let cloned_station = self.station.clone();
-
-        let consumer_name = self.get_name();
-        let durable_name = self.get_internal_name();
+        let consumer = self.handle();
 
         let handle = tokio::spawn(async move {
-            async fn ping_stream_consumer(
-                stream_name: &str,
-                consumer_name: &str,
-                client: &MemphisClient,
-            ) -> Result<(), async_nats::Error> {
-                let stream = client
-                    .get_jetstream_context()
-                    .get_stream(stream_name)
-                    .await?;
-
-                let _consumer = stream.consumer_info(&consumer_name).await?;
-
-                Ok(())
-            }
-            while !cloned_token.is_cancelled() {
-                tokio::time::sleep(Duration::from_secs(30)).await;
-                match &cloned_partitions_data {
-                    None => {
-                        let res = ping_stream_consumer(
-                            &cloned_station.get_internal_name(None),
-                            &durable_name,
-                            &cloned_client,
-                        )
-                        .await;
-                        if let Err(e) = res {
-                            error!("Error pinging consumer. {}", e);
-                            continue;
-                        } else {
-                            trace!(
-                                "Consumer '{}' on station '{}' is still alive.",
-                                &consumer_name,
-                                &cloned_station.options.station_name
-                            )
+            while !consumer.cancellation_token.is_cancelled() {
+                tokio::select! {
+                    _ = tokio::time::sleep(consumer.options.ping_interval) => {},
+                    _ = consumer.registration.partitions_changed.notified() => {
+                        if let Err(e) = consumer.restart_pull_subscriptions().await {
+                            error!("Error restarting the pull subscriptions. {}", e);
                         }
+                        continue;
                     }
-                    Some(data) => {
-                        for x in data {
-                            let res = ping_stream_consumer(
-                                &cloned_station.get_internal_name(Some(*x)),
-                                &durable_name,
-                                &cloned_client,
-                            )
-                            .await;
-                            if let Err(e) = res {
-                                error!("Error pinging consumer. {}", e);
-                                continue;
-                            } else {
-                                trace!(
-                                    "Consumer '{}' on station '{}' is still alive.",
-                                    &consumer_name,
-                                    &cloned_station.options.station_name
-                                )
-                            }
-                        }
+                }
+                if !consumer.station.memphis_client.is_connected() {
+                    continue;
+                }
+                if let Err(event) = consumer.ping().await {
+                    error!("Error pinging consumer. {:?}", event);
+                    let lost = is_consumer_lost(&event);
+                    let _ = consumer.events.send(event);
+                    if let (true, Some(recovery)) = (lost, &consumer.options.recovery) {
+                        consumer.recover(recovery).await;
                     }
-                };
+                } else {
+                    trace!(
+                        "Consumer '{}' on station '{}' is still alive.",
+                        &consumer.options.consumer_name,
+                        &consumer.station.options.station_name
+                    )
+                }
             }
         });
 
@@ -451,6 +781,138 @@ impl MemphisConsumer {
         });
     }
 
+    /// Checks that the stream and the consumer of every partition still exist.
+    async fn ping(&self) -> Result<(), MemphisEvent> {
+        let partitions = partitions(&*self.partitions_list.read().await);
+        let durable_name = self.get_internal_name();
+
+        for partition in partitions {
+            let stream = self
+                .station
+                .memphis_client
+                .get_jetstream_context()
+                .get_stream(self.station.get_internal_name(partition))
+                .await
+                .map_err(|e| MemphisEvent::StationUnavailable(Arc::new(e)))?;
+
+            // Requested directly, as `consumer_info` does not keep the error code of the response.
+            let subject = format!(
+                "CONSUMER.INFO.{}.{}",
+                stream.cached_info().config.name,
+                durable_name
+            );
+            let response: Response<jetstream::consumer::Info> = self
+                .station
+                .memphis_client
+                .get_jetstream_context()
+                .request(subject, &serde_json::json!({}))
+                .await
+                .map_err(|e| MemphisEvent::ConsumerUnavailable(Arc::new(e.into())))?;
+            if let Response::Err { error } = response {
+                return Err(MemphisEvent::ConsumerUnavailable(Arc::new(error.into())));
+            }
+        }
+        Ok(())
+    }
+
+    /// Tries to recreate the lost consumer, with an exponential backoff between the attempts.
+    /// Attempts are neither made nor counted while the client is disconnected.
+    /// Stops the consumer if all attempts failed.
+    async fn recover(&self, recovery: &ConsumerRecoveryOptions) {
+        let mut backoff = recovery.initial_backoff;
+        let mut attempt = 0;
+
+        while attempt < recovery.max_retries {
+            tokio::select! {
+                _ = tokio::time::sleep(backoff) => {},
+                _ = self.cancellation_token.cancelled() => return,
+            }
+            if !self.station.memphis_client.is_connected() {
+                continue;
+            }
+
+            match self.resubscribe().await {
+                Ok(()) => {
+                    info!("Consumer '{}' was recovered.", &self.options.consumer_name);
+                    let _ = self.events.send(MemphisEvent::ConsumerRecovered);
+                    return;
+                }
+                // The connection was lost during the attempt.
+                Err(_) if !self.station.memphis_client.is_connected() => continue,
+                Err(e) => {
+                    attempt += 1;
+                    warn!(
+                        "Error recovering consumer '{}' (attempt {}/{}). {}",
+                        &self.options.consumer_name, attempt, recovery.max_retries, e
+                    );
+                }
+            }
+            backoff = (backoff * 2).min(recovery.max_backoff);
+        }
+
+        error!(
+            "Consumer '{}' could not be recovered. Stopping consumer.",
+            &self.options.consumer_name
+        );
+        let _ = self.events.send(MemphisEvent::RecoveryFailed);
+        self.stop();
+    }
+
+    /// Creates the consumer again and restarts the pull subscriptions of all receivers.
+    async fn resubscribe(&self) -> Result<(), Error> {
+        let partitions_list = self.register().await?;
+        *self.partitions_list.write().await = partitions_list;
+        self.restart_pull_subscriptions().await
+    }
+
+    /// Stops the pull subscriptions of all receivers and starts them for the current partitions.
+    async fn restart_pull_subscriptions(&self) -> Result<(), Error> {
+        let partitions = partitions(&*self.partitions_list.read().await);
+
+        let (cancellation_token, senders) = {
+            let mut subscriptions = self.subscriptions();
+            subscriptions.token.cancel();
+            subscriptions.token = self.cancellation_token.child_token();
+            subscriptions.senders.retain(|sender| !sender.is_closed());
+            (subscriptions.token.clone(), subscriptions.senders.clone())
+        };
+
+        for sender in senders {
+            for partition in &partitions {
+                self.start_pull_subscription(
+                    *partition,
+                    sender.clone(),
+                    cancellation_token.clone(),
+                )
+                .await?;
+            }
+        }
+        Ok(())
+    }
+
+    fn subscriptions(&self) -> MutexGuard<'_, Subscriptions> {
+        self.subscriptions
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Returns a handle sharing the state of this consumer, used by its background tasks.
+    fn handle(&self) -> Self {
+        Self {
+            station: self.station.clone(),
+            options: self.options.clone(),
+            cancellation_token: self.cancellation_token.clone(),
+            partitions_list: self.partitions_list.clone(),
+            events: self.events.clone(),
+            subscriptions: self.subscriptions.clone(),
+            ack_scheduler: self.ack_scheduler.clone(),
+            dedupe: self.dedupe.clone(),
+            fetch_cursor: self.fetch_cursor.clone(),
+            registration: self.registration.clone(),
+            registry_entry: None,
+        }
+    }
+
     /// Get the internal name of the consumer. This is the name of the consumer in Jetstream.
     pub fn get_internal_name(&self) -> String {
         if self.options.consumer_group.is_empty() {
@@ -460,3 +922,124 @@ impl MemphisConsumer {
         }
     }
 }
+
+/// Whether the ping failed because the server no longer knows the consumer.
+fn is_consumer_lost(event: &MemphisEvent) -> bool {
+    match event {
+        MemphisEvent::ConsumerUnavailable(e) => e
+            .downcast_ref::<jetstream::Error>()
+            .is_some_and(|e| e.error_code() == ErrorCode::CONSUMER_NOT_FOUND),
+        _ => false,
+    }
+}
+
+/// A dropped consumer is stopped, see [stop](MemphisConsumer::stop). It is no longer registered
+/// again after a reconnect, nor destroyed on [shutdown](MemphisClient::shutdown).
+impl Drop for MemphisConsumer {
+    fn drop(&mut self) {
+        if self.registry_entry.is_some() {
+            self.stop();
+        }
+    }
+}
+
+/// Returns the partitions to subscribe to. `None` stands for a station without partitions.
+fn partitions(partitions_list: &Option<Vec<u32>>) -> Vec<Option<u32>> {
+    match partitions_list {
+        None => vec![None],
+        Some(list) => list.iter().map(|partition| Some(*partition)).collect(),
+    }
+}
+
+/// The state needed to register the consumer again after a reconnect, and to stop and destroy it
+/// when the client shuts down. Does not hold the station, so the client is not kept alive by its own registry.
+struct ConsumerRegistration {
+    consumer_name: String,
+    station_name: String,
+    /// The request the consumer was created with.
+    create_request: serde_json::Value,
+    cancellation_token: CancellationToken,
+    subscriptions: Arc<Mutex<Subscriptions>>,
+    events: broadcast::Sender<MemphisEvent>,
+    in_flight: watch::Receiver<usize>,
+    partitions_list: Arc<RwLock<Option<Vec<u32>>>>,
+    /// Notifies the consumer to restart its pull subscriptions, see [MemphisConsumer::ping_consumer].
+    partitions_changed: Notify,
+}
+
+#[async_trait::async_trait]
+impl Resource for ConsumerRegistration {
+    fn stop(&self) {
+        if !self.cancellation_token.is_cancelled() {
+            self.cancellation_token.cancel();
+            // Closes the receivers once the pull subscriptions have finished.
+            self.subscriptions
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .senders
+                .clear();
+            let _ = self.events.send(MemphisEvent::ConsumerStopped);
+        }
+    }
+
+    async fn wait_idle(&self) {
+        // Fails if the consumer and all of its messages were dropped, so nothing is in flight.
+        let _ = self
+            .in_flight
+            .clone()
+            .wait_for(|in_flight| *in_flight == 0)
+            .await;
+    }
+
+    /// If the partitions changed, the consumer restarts its pull subscriptions.
+    async fn reregister(&self, connection: &Client) -> Result<(), RequestError> {
+        let res = send_request(
+            connection,
+            &self.create_request,
+            MemphisSpecialStation::ConsumerCreations,
+        )
+        .await?;
+
+        // Brokers without partitions send an empty response.
+        let partitions_list = if res.payload.is_empty() {
+            None
+        } else {
+            let res = serde_json::from_slice::<CreateConsumerResponse>(&res.payload)?;
+            Some(res.partitions_update.partitions_list)
+        };
+        let mut current_partitions_list = self.partitions_list.write().await;
+        if *current_partitions_list != partitions_list {
+            *current_partitions_list = partitions_list;
+            self.partitions_changed.notify_one();
+        }
+
+        info!("Registered consumer {} again.", &self.consumer_name);
+
+        Ok(())
+    }
+
+    async fn destroy(&self, client: &MemphisClient) -> Result<(), RequestError> {
+        let destroy_request = DestroyConsumerRequest {
+            consumer_name: &self.consumer_name,
+            station_name: &self.station_name,
+            username: &client.username,
+            connection_id: &client.connection_id,
+            req_version: 1,
+        };
+
+        if let Err(e) = client
+            .send_internal_request(
+                &destroy_request,
+                MemphisSpecialStation::ConsumerDestructions,
+            )
+            .await
+        {
+            error!("Error destroying consumer. {}", &e);
+            return Err(e);
+        }
+
+        info!("Destroyed consumer {}.", &self.consumer_name);
+
+        Ok(())
+    }
+}
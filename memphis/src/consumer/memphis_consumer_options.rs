@@ -26,6 +26,13 @@ pub struct MemphisConsumerOptions {
     pub generate_unique_suffix: bool,
     pub start_consume_from_sequence: i32,
     pub last_messages: i32,
+    /// The number of received but unsettled messages remembered to drop redeliveries.
+    /// `0` disables the deduplication.
+    pub dedupe_capacity: usize,
+    /// How often Memphis is asked whether the consumer still exists.
+    pub ping_interval: Duration,
+    /// Recovers the consumer if it is lost, e.g. after a broker restart. Disabled by default.
+    pub recovery: Option<ConsumerRecoveryOptions>,
 }
 
 impl Default for MemphisConsumerOptions {
@@ -41,6 +48,9 @@ impl Default for MemphisConsumerOptions {
             generate_unique_suffix: false,
             start_consume_from_sequence: 1,
             last_messages: -1,
+            dedupe_capacity: 10_000,
+            ping_interval: Duration::from_secs(30),
+            recovery: None,
         }
     }
 }
@@ -102,4 +112,79 @@ impl MemphisConsumerOptions {
         self.last_messages = last_messages;
         self
     }
+
+    /// Sets how many received but unsettled messages are remembered, so a message which is
+    /// redelivered before it was acknowledged is not received twice.
+    /// The oldest messages are forgotten first. `0` disables the deduplication.
+    pub fn with_dedupe_capacity(mut self, dedupe_capacity: usize) -> Self {
+        self.dedupe_capacity = dedupe_capacity;
+        self
+    }
+
+    /// Sets how often Memphis is asked whether the consumer still exists.
+    /// A lost consumer is noticed, and recovered, after at most this interval.
+    pub fn with_ping_interval(mut self, ping_interval: Duration) -> Self {
+        self.ping_interval = ping_interval;
+        self
+    }
+
+    /// Enables the recovery of the consumer. See [ConsumerRecoveryOptions].
+    pub fn with_recovery(mut self, recovery: ConsumerRecoveryOptions) -> Self {
+        self.recovery = Some(recovery);
+        self
+    }
+}
+
+/// Options for recovering a consumer which was lost, e.g. after a broker restart or after the
+/// station was recreated.
+///
+/// Once a ping finds that the server no longer knows the consumer, it is created again and the
+/// pull subscriptions of every partition are restarted. Messages are delivered to the same
+/// receivers as before. Failed attempts are retried with an exponential backoff, once
+/// `max_retries` attempts have failed the consumer is stopped. While the client is disconnected,
+/// no attempts are made or counted.
+///
+/// # Example
+/// ```rust
+/// use memphis_rust_community::consumer::{ConsumerRecoveryOptions, MemphisConsumerOptions};
+/// use std::time::Duration;
+///
+/// let options = MemphisConsumerOptions::new("consumer_name").with_recovery(
+///     ConsumerRecoveryOptions::default()
+///         .with_initial_backoff(Duration::from_millis(500))
+///         .with_max_retries(20),
+/// );
+/// ```
+#[derive(Debug, Clone)]
+pub struct ConsumerRecoveryOptions {
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+    pub max_retries: u32,
+}
+
+impl Default for ConsumerRecoveryOptions {
+    fn default() -> Self {
+        ConsumerRecoveryOptions {
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(30),
+            max_retries: 10,
+        }
+    }
+}
+
+impl ConsumerRecoveryOptions {
+    pub fn with_initial_backoff(mut self, initial_backoff: Duration) -> Self {
+        self.initial_backoff = initial_backoff;
+        self
+    }
+
+    pub fn with_max_backoff(mut self, max_backoff: Duration) -> Self {
+        self.max_backoff = max_backoff;
+        self
+    }
+
+    pub fn with_max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
 }
@@ -0,0 +1,18 @@
+use crate::request_error::RequestError;
+use thiserror::Error;
+
+/// Returned when acknowledging a [MemphisMessage](crate::consumer::MemphisMessage) or reading its metadata fails.
+#[derive(Error, Debug)]
+pub enum MessageError {
+    /// The acknowledgement could not be sent to the broker.
+    #[error("AckError: {0}")]
+    AckError(async_nats::Error),
+
+    /// The acknowledgement of a poison message could not be sent to Memphis.
+    #[error("RequestError: {0}")]
+    RequestError(#[from] RequestError),
+
+    /// The reply subject of the message does not contain its metadata.
+    #[error("MetadataError: {0}")]
+    MetadataError(async_nats::Error),
+}
@@ -0,0 +1,54 @@
+use std::future::Future;
+use std::time::Duration;
+
+use crate::consumer::MemphisMessage;
+
+/// The first delay of a message that is retried with [Nack::Retry].
+pub const MIN_RETRY_BACKOFF: Duration = Duration::from_secs(1);
+/// The longest delay of a message that is retried with [Nack::Retry].
+pub const MAX_RETRY_BACKOFF: Duration = Duration::from_secs(60);
+
+/// Returned by a [MessageHandler] if the message was processed successfully.
+/// The message is acknowledged.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Ack;
+
+/// Returned by a [MessageHandler] if the message could not be processed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Nack {
+    /// The error is retryable. The message is delayed with an exponential backoff,
+    /// starting at [MIN_RETRY_BACKOFF] and capped at [MAX_RETRY_BACKOFF].
+    Retry,
+    /// The error is retryable. The message is delayed for the given duration.
+    RetryAfter(Duration),
+    /// The message is not acknowledged. Memphis redelivers it once the max ack time is exceeded
+    /// and sends it to the DLS once the max message deliveries are reached.
+    Reject,
+}
+
+/// Processes the messages of [MemphisConsumer::run](crate::consumer::MemphisConsumer::run).
+///
+/// Implemented for every async closure taking a [MemphisMessage] and returning `Result<Ack, Nack>`.
+#[async_trait::async_trait]
+pub trait MessageHandler: Send + Sync + 'static {
+    async fn handle(&self, message: MemphisMessage) -> Result<Ack, Nack>;
+}
+
+#[async_trait::async_trait]
+impl<F, Fut> MessageHandler for F
+where
+    F: Fn(MemphisMessage) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = Result<Ack, Nack>> + Send,
+{
+    async fn handle(&self, message: MemphisMessage) -> Result<Ack, Nack> {
+        self(message).await
+    }
+}
+
+/// Returns the delay of a message retried with [Nack::Retry], after it was delivered `delivered` times.
+pub(crate) fn retry_backoff(delivered: u64) -> Duration {
+    let exponent = delivered.saturating_sub(1).min(16) as u32;
+    MIN_RETRY_BACKOFF
+        .saturating_mul(2u32.pow(exponent))
+        .min(MAX_RETRY_BACKOFF)
+}
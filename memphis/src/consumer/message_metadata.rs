@@ -0,0 +1,29 @@
+use std::time::SystemTime;
+
+/// The metadata of a [MemphisMessage](crate::consumer::MemphisMessage).
+/// See [metadata](crate::consumer::MemphisMessage::metadata).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MessageMetadata {
+    /// The sequence number of the message in its station partition.
+    pub stream_sequence: u64,
+    /// The sequence number of the delivery to this consumer.
+    pub consumer_sequence: u64,
+    /// How often the message was delivered, including this delivery.
+    pub delivery_count: u64,
+    /// When the message was received by the broker.
+    pub published: SystemTime,
+    /// The number of messages which are pending for this consumer.
+    pub pending: u64,
+    /// The partition the message was stored in, `None` if the station has no partitions.
+    pub partition: Option<u32>,
+    /// The name of the producer, taken from the `$memphis_producedBy` header.
+    pub producer_name: Option<String>,
+    /// The connection id of the producer, taken from the `$memphis_connectionId` header.
+    pub connection_id: Option<String>,
+}
+
+/// Returns the partition of a stream named `<station>$<partition>`.
+pub(crate) fn partition_of_stream(stream: &str) -> Option<u32> {
+    let (_, partition) = stream.rsplit_once('$')?;
+    partition.parse().ok()
+}
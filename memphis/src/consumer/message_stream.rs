@@ -0,0 +1,64 @@
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use futures_util::Stream;
+use tokio::sync::mpsc::error::SendError;
+use tokio::sync::mpsc::{Receiver, Sender, UnboundedSender};
+
+use crate::consumer::MemphisMessage;
+
+/// A [Stream] of the messages received by a [MemphisConsumer](crate::consumer::MemphisConsumer).
+///
+/// The stream is backed by a bounded channel. Once it is full, the consumer stops fetching
+/// messages from Memphis until the application catches up.
+/// See [consume_stream](crate::consumer::MemphisConsumer::consume_stream).
+pub struct MemphisMessageStream {
+    receiver: Receiver<MemphisMessage>,
+}
+
+impl MemphisMessageStream {
+    pub(crate) fn new(receiver: Receiver<MemphisMessage>) -> Self {
+        Self { receiver }
+    }
+
+    /// Closes the stream. Messages which were already received can still be read.
+    pub fn close(&mut self) {
+        self.receiver.close();
+    }
+}
+
+impl Stream for MemphisMessageStream {
+    type Item = MemphisMessage;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.receiver.poll_recv(cx)
+    }
+}
+
+/// The sending side of the channel the pull subscriptions pass their messages to.
+#[derive(Clone)]
+pub(crate) enum MessageSender {
+    Unbounded(UnboundedSender<MemphisMessage>),
+    /// Waits for free capacity, which pauses the pull subscription.
+    Bounded(Sender<MemphisMessage>),
+}
+
+impl MessageSender {
+    pub(crate) async fn send(
+        &self,
+        message: MemphisMessage,
+    ) -> Result<(), SendError<MemphisMessage>> {
+        match self {
+            MessageSender::Unbounded(sender) => sender.send(message),
+            MessageSender::Bounded(sender) => sender.send(message).await,
+        }
+    }
+
+    /// Whether the receiver was dropped or closed.
+    pub(crate) fn is_closed(&self) -> bool {
+        match self {
+            MessageSender::Unbounded(sender) => sender.is_closed(),
+            MessageSender::Bounded(sender) => sender.is_closed(),
+        }
+    }
+}
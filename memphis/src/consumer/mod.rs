@@ -1,11 +1,25 @@
 pub use consumer_error::*;
+pub use dls_message::*;
 pub use event::*;
 pub use incoming_message::*;
 pub use memphis_consumer::*;
 pub use memphis_consumer_options::*;
+pub use message_error::*;
+pub use message_handler::*;
+pub use message_metadata::*;
+pub use message_stream::*;
+pub use typed_consumer::*;
 
+mod ack_scheduler;
 mod consumer_error;
+mod dedupe;
+mod dls_message;
 mod event;
 mod incoming_message;
 mod memphis_consumer;
 mod memphis_consumer_options;
+mod message_error;
+mod message_handler;
+mod message_metadata;
+mod message_stream;
+mod typed_consumer;
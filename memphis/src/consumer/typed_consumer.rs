@@ -0,0 +1,111 @@
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+use async_nats::Error;
+use log::{error, warn};
+use serde::de::DeserializeOwned;
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver};
+
+use crate::codec::CodecError;
+use crate::consumer::{MemphisConsumer, MemphisMessage};
+
+/// Called with every message that could not be decoded by a [TypedConsumer].
+pub type PoisonHandler = Arc<dyn Fn(MemphisMessage, CodecError) + Send + Sync>;
+
+/// A consumer that deserializes the messages it receives into values of type `T`.
+///
+/// Each message is decoded with the codec matching its `content-type` header.
+/// Messages that can not be decoded are passed to the poison handler instead of the application.
+/// By default, they are logged and left unacknowledged, so Memphis sends them to the DLS
+/// once the maximum number of deliveries is reached.
+///
+/// # Example
+/// ```rust
+/// use memphis_rust_community::memphis_client::MemphisClient;
+/// use memphis_rust_community::consumer::{MemphisConsumerOptions, TypedConsumer};
+/// use memphis_rust_community::station::MemphisStationsOptions;
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// struct Payment {
+///     id: u64,
+///     amount: f64,
+/// }
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let client = MemphisClient::new("localhost:6666", "root", "memphis", None).await.unwrap();
+///     let station = client.create_station(MemphisStationsOptions::new("payments")).await.unwrap();
+///
+///     let consumer = station.create_consumer(MemphisConsumerOptions::new("billing")).await.unwrap();
+///     let consumer = TypedConsumer::<Payment>::new(consumer).with_poison_handler(|msg, e| {
+///         eprintln!("Invalid payment {:?}: {}", msg, e);
+///     });
+///
+///     let mut receiver = consumer.consume().await.unwrap();
+///     while let Some((payment, msg)) = receiver.recv().await {
+///         println!("Payment {} of {}", payment.id, payment.amount);
+///         msg.ack().await.unwrap();
+///     }
+/// }
+/// ```
+pub struct TypedConsumer<T> {
+    consumer: MemphisConsumer,
+    poison_handler: PoisonHandler,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T: DeserializeOwned + Send + 'static> TypedConsumer<T> {
+    pub fn new(consumer: MemphisConsumer) -> Self {
+        Self {
+            consumer,
+            poison_handler: Arc::new(|msg, e| {
+                warn!("Could not decode message {:?}. {}", msg, e);
+            }),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Sets the handler called with every message that could not be decoded.
+    pub fn with_poison_handler(
+        mut self,
+        poison_handler: impl Fn(MemphisMessage, CodecError) + Send + Sync + 'static,
+    ) -> Self {
+        self.poison_handler = Arc::new(poison_handler);
+        self
+    }
+
+    /// Starts consuming messages and decoding them.
+    ///
+    /// For more details, see [consume](MemphisConsumer::consume).
+    pub async fn consume(&self) -> Result<UnboundedReceiver<(T, MemphisMessage)>, Error> {
+        let mut messages = self.consumer.consume().await?;
+        let (sender, receiver) = unbounded_channel();
+        let poison_handler = self.poison_handler.clone();
+
+        tokio::spawn(async move {
+            while let Some(msg) = messages.recv().await {
+                match msg.deserialize::<T>() {
+                    Ok(value) => {
+                        if sender.send((value, msg)).is_err() {
+                            error!("Error while sending decoded message to the receiver.");
+                            break;
+                        }
+                    }
+                    Err(e) => poison_handler(msg, e),
+                }
+            }
+        });
+
+        Ok(receiver)
+    }
+
+    /// Returns the underlying consumer.
+    pub fn consumer(&self) -> &MemphisConsumer {
+        &self.consumer
+    }
+
+    pub fn into_inner(self) -> MemphisConsumer {
+        self.consumer
+    }
+}
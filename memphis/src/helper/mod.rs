@@ -1,2 +1 @@
 pub(crate) mod memphis_util;
-pub(crate) mod partition_iterator;
@@ -30,8 +30,10 @@
 //! ```
 #![forbid(unsafe_code)]
 
-pub use request_error::RequestError;
+pub use memphis_error::MemphisError;
+pub use request_error::{MemphisServerError, RequestError};
 
+pub mod codec;
 pub mod memphis_client;
 
 #[cfg(feature = "consumers")]
@@ -47,4 +49,5 @@ pub(crate) mod constants;
 pub(crate) mod helper;
 pub(crate) mod models;
 
+mod memphis_error;
 mod request_error;
@@ -5,18 +5,31 @@ use std::time::Duration;
 use crate::constants::memphis_constants::MemphisNotificationType;
 use async_nats::connection::State;
 use async_nats::jetstream::Context;
-use async_nats::{jetstream, Client, ConnectError, ConnectOptions, Event, Message};
+use async_nats::{jetstream, Client, ConnectError, ConnectOptions, Message};
 use bytes::Bytes;
-use log::debug;
+use futures_util::future::join_all;
+use log::{error, warn};
 use serde::Serialize;
+use tokio::sync::broadcast;
+use tokio::sync::broadcast::error::RecvError;
 use uuid::Uuid;
 
 use crate::constants::memphis_constants::MemphisSpecialStation;
 #[cfg(feature = "schemaverse")]
 use crate::models::request::NotificationRequest;
-use crate::request_error::RequestError;
+use crate::request_error::{MemphisServerError, RequestError};
+#[cfg(feature = "schemaverse")]
+use crate::schemaverse::schema_updates::SchemaUpdatesRegistry;
 use crate::station::{MemphisStation, MemphisStationsOptions};
 
+pub use client_event::*;
+pub use memphis_client_options::*;
+pub(crate) use resource_registry::*;
+
+mod client_event;
+mod memphis_client_options;
+mod resource_registry;
+
 /// # Memphis Client
 ///
 /// The Memphis Client is used to connect to Memphis.
@@ -35,6 +48,13 @@ pub struct MemphisClient {
     broker_connection: Arc<Client>,
     pub(crate) username: Arc<String>,
     pub(crate) connection_id: Arc<String>,
+    /// How long to wait for the responses of Memphis. `None` waits forever.
+    #[cfg(feature = "schemaverse")]
+    pub(crate) request_timeout: Option<Duration>,
+    events: ClientEvents,
+    pub(crate) registry: ResourceRegistry,
+    #[cfg(feature = "schemaverse")]
+    pub(crate) schema_updates: SchemaUpdatesRegistry,
 }
 
 impl MemphisClient {
@@ -64,35 +84,36 @@ impl MemphisClient {
         memphis_password: &str,
         account_id: Option<&str>,
     ) -> Result<MemphisClient, ConnectError> {
-        let uuid = Uuid::new_v4();
-        let connection_name = format!("{}::{}", &uuid, memphis_username);
+        let options = MemphisClientOptions::new(memphis_host, memphis_username)
+            .with_password(memphis_password)
+            .with_account_id(account_id.unwrap_or("1"));
+        MemphisClient::connect(options).await
+    }
 
-        let account_id = account_id.unwrap_or("1");
+    /// Creates a new MemphisClient with the given options.
+    /// Use this for credentials other than a password, TLS or multiple seed servers.
+    /// See [MemphisClientOptions] for an example.
+    pub async fn connect(options: MemphisClientOptions) -> Result<MemphisClient, ConnectError> {
+        let uuid = Uuid::new_v4();
+        let connection_name = format!("{}::{}", &uuid, &options.username);
+        let servers = options.servers.join(",");
+        let events = ClientEvents::new();
 
-        let broker_settings = MemphisClient::create_settings(
-            format!("{}${}", memphis_username, account_id).as_str(),
-            memphis_password,
-            connection_name.clone(),
-        );
+        let broker_settings =
+            MemphisClient::create_settings(&options, true, connection_name.clone(), &events)
+                .await?;
 
-        let connection = match async_nats::connect_with_options(memphis_host, broker_settings).await
+        let connection = match async_nats::connect_with_options(servers.as_str(), broker_settings)
+            .await
         {
             Ok(c) => c,
             Err(e) => {
-                if e.to_string().contains("authorization violation") {
-                    let broker_settings = MemphisClient::create_settings(
-                        memphis_username,
-                        memphis_password,
-                        connection_name,
-                    );
-                    let connection =
-                        async_nats::connect_with_options(memphis_host, broker_settings).await;
-                    match connection {
-                        Ok(c) => c,
-                        Err(e) => {
-                            return Err(e);
-                        }
-                    }
+                let is_password = matches!(options.credentials, MemphisCredentials::Password(_));
+                if is_password && e.to_string().contains("authorization violation") {
+                    let broker_settings =
+                        MemphisClient::create_settings(&options, false, connection_name, &events)
+                            .await?;
+                    async_nats::connect_with_options(servers.as_str(), broker_settings).await?
                 } else {
                     return Err(e);
                 }
@@ -102,20 +123,126 @@ impl MemphisClient {
         while connection.connection_state() == State::Pending {
             tokio::time::sleep(Duration::from_millis(50)).await;
         }
+        // The connected event may be delivered after the connection was established.
+        if connection.connection_state() == State::Connected {
+            events.set_connected(true);
+        }
 
-        Ok(MemphisClient {
+        let client = MemphisClient {
             jetstream_context: Arc::new(jetstream::new(connection.clone())),
             broker_connection: Arc::new(connection),
-            username: Arc::new(memphis_username.to_string()),
+            username: Arc::new(options.username),
             connection_id: Arc::new(uuid.to_string()),
-        })
+            #[cfg(feature = "schemaverse")]
+            request_timeout: options.request_timeout,
+            events,
+            registry: ResourceRegistry::default(),
+            #[cfg(feature = "schemaverse")]
+            schema_updates: SchemaUpdatesRegistry::default(),
+        };
+        client.reregister_on_reconnect();
+
+        Ok(client)
     }
 
+    /// Registers the producers and consumers of this client again, whenever the connection
+    /// was re-established. Memphis forgets about them if the broker is restarted.
+    ///
+    /// The task does not keep the connection alive, it stops once the client was dropped.
+    fn reregister_on_reconnect(&self) {
+        let mut events = self.events();
+        let connection = Arc::downgrade(&self.broker_connection);
+        let registry = self.registry.clone();
+
+        tokio::spawn(async move {
+            let mut disconnected = false;
+            loop {
+                let reconnected = match events.recv().await {
+                    Ok(MemphisClientEvent::Disconnected) => {
+                        disconnected = true;
+                        false
+                    }
+                    Ok(MemphisClientEvent::Connected) => std::mem::take(&mut disconnected),
+                    Ok(_) => false,
+                    // A reconnect may have been missed.
+                    Err(RecvError::Lagged(_)) => true,
+                    Err(RecvError::Closed) => break,
+                };
+
+                if reconnected {
+                    let Some(connection) = connection.upgrade() else {
+                        break;
+                    };
+                    registry.reregister_all(&connection).await;
+                }
+            }
+        });
+    }
+
+    /// Whether the client is connected to the broker, as tracked from the connection events.
     pub fn is_connected(&self) -> bool {
-        match &self.broker_connection.connection_state() {
-            State::Pending => false,
-            State::Connected => true,
-            State::Disconnected => false,
+        self.events.is_connected()
+    }
+
+    /// Returns a receiver for the lifecycle events of the connection, like disconnects and reconnects.
+    /// Only events which occur after subscribing are received.
+    pub fn events(&self) -> broadcast::Receiver<MemphisClientEvent> {
+        self.events.subscribe()
+    }
+
+    /// # Shuts the client down gracefully.
+    /// Stops all consumers, waits up to `timeout` for their received messages to be settled,
+    /// and destroys all producers and consumers of this client which were not destroyed yet.
+    /// Finally the pending messages are flushed to the broker.
+    ///
+    /// All producers and consumers are destroyed, even if some of them fail. The first error is returned.
+    ///
+    /// # Note
+    /// async-nats does not support draining the connection yet. The connection is closed once
+    /// the last clone of the client is dropped.
+    ///
+    /// # Example
+    /// ```rust
+    /// use std::time::Duration;
+    /// use memphis_rust_community::memphis_client::MemphisClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let client = MemphisClient::new("localhost:6666", "root", "memphis", None).await.unwrap();
+    ///
+    ///     // Create stations, producers and consumers
+    ///
+    ///     client.shutdown(Duration::from_secs(10)).await.unwrap();
+    /// }
+    /// ```
+    pub async fn shutdown(&self, timeout: Duration) -> Result<(), RequestError> {
+        let resources = self.registry.take_all();
+        for resource in &resources {
+            resource.stop();
+        }
+
+        let idle = join_all(resources.iter().map(|resource| resource.wait_idle()));
+        if tokio::time::timeout(timeout, idle).await.is_err() {
+            warn!(
+                "Shutting down with messages which were not acknowledged within {:?}.",
+                timeout
+            );
+        }
+
+        let results = join_all(resources.iter().map(|resource| resource.destroy(self))).await;
+
+        self.broker_connection
+            .flush()
+            .await
+            .map_err(|e| RequestError::NatsError(e.into()))?;
+
+        let mut errors = results.into_iter().filter_map(Result::err);
+        match errors.next() {
+            Some(e) => {
+                error!("Error shutting down the client. {}", e);
+                Err(e)
+            }
+            None => Ok(()),
         }
     }
 
@@ -167,56 +294,102 @@ impl MemphisClient {
             return Err(RequestError::NotConnected);
         }
 
-        let create_consumer_model_json = serde_json::to_string(&request)?;
-        let create_consumer_model_bytes = Bytes::from(create_consumer_model_json);
-        let res = self
-            .get_broker_connection()
-            .request(request_type.to_string(), create_consumer_model_bytes)
-            .await
-            .map_err(|e| RequestError::NatsError(e.into()))?;
-
-        let error_message = std::str::from_utf8(&res.payload)
-            .map_err(|e| RequestError::MemphisError(e.to_string()))?;
-
-        if !error_message.trim().is_empty() {
-            let parsed_json: serde_json::Value = serde_json::from_str(error_message)?;
-            let raw_object = if let Some(obj) = parsed_json.as_object() {
-                obj
-            } else {
-                return Err(RequestError::MemphisError("Error parsing json".to_string()));
-            };
-            if let Some((_key, value)) = raw_object.get_key_value("error") {
-                let value = value.as_str().unwrap_or("Error was not a string");
-                return if value.to_string().trim().is_empty() {
-                    Ok(res)
+        send_request(self.get_broker_connection(), request, request_type).await
+    }
+    /// Creates the settings of the broker connection.
+    /// With a password, the username is suffixed with the account id, unless `with_account_id` is false.
+    async fn create_settings(
+        options: &MemphisClientOptions,
+        with_account_id: bool,
+        connection_name: String,
+        events: &ClientEvents,
+    ) -> Result<ConnectOptions, ConnectError> {
+        let settings = match &options.credentials {
+            MemphisCredentials::Password(password) => {
+                let username = if with_account_id {
+                    format!("{}${}", options.username, options.account_id)
                 } else {
-                    Err(RequestError::MemphisError(value.to_string()))
+                    options.username.clone()
                 };
+                ConnectOptions::with_user_and_password(username, password.clone())
             }
+            MemphisCredentials::Token(token) => ConnectOptions::with_token(token.clone()),
+            MemphisCredentials::Nkey(seed) => ConnectOptions::with_nkey(seed.clone()),
+            MemphisCredentials::CredentialsFile(path) => {
+                ConnectOptions::with_credentials_file(path).await?
+            }
+            MemphisCredentials::Credentials(credentials) => {
+                ConnectOptions::with_credentials(credentials)?
+            }
+        };
+
+        let mut settings = settings
+            .event_callback({
+                let events = events.clone();
+                move |event| {
+                    events.on_event(event);
+                    async {}
+                }
+            })
+            .reconnect_delay_callback({
+                let events = events.clone();
+                move |attempt| events.on_reconnect(attempt)
+            })
+            .retry_on_initial_connect()
+            .connection_timeout(options.connection_timeout)
+            .ping_interval(options.ping_interval)
+            .subscription_capacity(options.subscription_capacity)
+            .request_timeout(options.request_timeout)
+            .require_tls(options.tls.require_tls)
+            .name(connection_name);
+
+        for root_certificate in &options.tls.root_certificates {
+            settings = settings.add_root_certificates(root_certificate.clone());
+        }
+        if let Some((cert, key)) = &options.tls.client_certificate {
+            settings = settings.add_client_certificate(cert.clone(), key.clone());
         }
 
-        Ok(res)
+        Ok(settings)
     }
+}
 
-    fn create_settings(
-        memphis_username: &str,
-        memphis_password: &str,
-        connection_name: String,
-    ) -> ConnectOptions {
-        ConnectOptions::with_user_and_password(
-            memphis_username.to_string(),
-            memphis_password.to_string(),
-        )
-        .event_callback(MemphisClient::event_callback)
-        .retry_on_initial_connect()
-        .connection_timeout(Duration::from_secs(5))
-        .ping_interval(Duration::from_secs(1))
-        .subscription_capacity(1024)
-        .request_timeout(Some(Duration::from_secs(5)))
-        .name(connection_name)
-    }
+/// Sends a request to a internal/special Memphis Station and handles the errors.
+/// Unlike [send_internal_request](MemphisClient::send_internal_request), the connection state is not checked.
+pub(crate) async fn send_request(
+    connection: &Client,
+    request: &impl Serialize,
+    request_type: MemphisSpecialStation,
+) -> Result<Message, RequestError> {
+    let request_json = serde_json::to_string(&request)?;
+    let res = connection
+        .request(request_type.to_string(), Bytes::from(request_json))
+        .await
+        .map_err(|e| RequestError::NatsError(e.into()))?;
 
-    async fn event_callback(event: Event) {
-        debug!("Event: {:?}", event)
+    let error_message = std::str::from_utf8(&res.payload)
+        .map_err(|e| RequestError::InvalidResponse(e.to_string()))?;
+
+    if !error_message.trim().is_empty() {
+        let parsed_json: serde_json::Value = serde_json::from_str(error_message)?;
+        let raw_object = if let Some(obj) = parsed_json.as_object() {
+            obj
+        } else {
+            return Err(RequestError::InvalidResponse(
+                "Error parsing json".to_string(),
+            ));
+        };
+        if let Some((_key, value)) = raw_object.get_key_value("error") {
+            let value = value.as_str().unwrap_or("Error was not a string");
+            return if value.to_string().trim().is_empty() {
+                Ok(res)
+            } else {
+                Err(RequestError::ServerError(MemphisServerError::from_message(
+                    value.to_string(),
+                )))
+            };
+        }
     }
+
+    Ok(res)
 }
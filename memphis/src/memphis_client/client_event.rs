@@ -0,0 +1,97 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_nats::{ClientError, Event, ServerError};
+use log::{debug, warn};
+use tokio::sync::broadcast;
+
+/// The number of events kept for subscribers which are lagging behind.
+const EVENT_CHANNEL_CAPACITY: usize = 64;
+
+/// Lifecycle events of the connection of a [MemphisClient](crate::memphis_client::MemphisClient).
+/// See [events](crate::memphis_client::MemphisClient::events).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MemphisClientEvent {
+    /// The connection to the broker was established, or re-established after it was lost.
+    Connected,
+    /// The connection to the broker was lost.
+    Disconnected,
+    /// The client is about to try connecting to the broker. Also sent for the first connection.
+    Reconnecting { attempt: usize },
+    /// The broker is shutting down, the client will reconnect to another server.
+    LameDuckMode,
+    /// Messages of the given subscription were dropped, because they were not processed in time.
+    SlowConsumer { subscription_id: u64 },
+    /// The broker sent an error.
+    ServerError(ServerError),
+    /// The client ran into an error, e.g. a missing heartbeat.
+    ClientError(ClientError),
+}
+
+/// Tracks the connection state and publishes the events of a client.
+#[derive(Clone)]
+pub(crate) struct ClientEvents {
+    sender: broadcast::Sender<MemphisClientEvent>,
+    connected: Arc<AtomicBool>,
+}
+
+impl ClientEvents {
+    pub(crate) fn new() -> Self {
+        let (sender, _) = broadcast::channel(EVENT_CHANNEL_CAPACITY);
+        Self {
+            sender,
+            connected: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    pub(crate) fn subscribe(&self) -> broadcast::Receiver<MemphisClientEvent> {
+        self.sender.subscribe()
+    }
+
+    pub(crate) fn is_connected(&self) -> bool {
+        self.connected.load(Ordering::Acquire)
+    }
+
+    pub(crate) fn set_connected(&self, connected: bool) {
+        self.connected.store(connected, Ordering::Release);
+    }
+
+    /// Called by async-nats for every event of the connection.
+    pub(crate) fn on_event(&self, event: Event) {
+        debug!("Event: {:?}", event);
+        let event = match event {
+            Event::Connected => {
+                self.set_connected(true);
+                MemphisClientEvent::Connected
+            }
+            Event::Disconnected => {
+                self.set_connected(false);
+                MemphisClientEvent::Disconnected
+            }
+            Event::LameDuckMode => MemphisClientEvent::LameDuckMode,
+            Event::SlowConsumer(subscription_id) => {
+                warn!("Slow consumer on subscription {}", subscription_id);
+                MemphisClientEvent::SlowConsumer { subscription_id }
+            }
+            Event::ServerError(e) => MemphisClientEvent::ServerError(e),
+            Event::ClientError(e) => MemphisClientEvent::ClientError(e),
+        };
+        let _ = self.sender.send(event);
+    }
+
+    /// Called by async-nats before every connection attempt. Returns the delay before the attempt,
+    /// which is the same exponential backoff async-nats uses by default.
+    pub(crate) fn on_reconnect(&self, attempt: usize) -> Duration {
+        let _ = self
+            .sender
+            .send(MemphisClientEvent::Reconnecting { attempt });
+
+        if attempt <= 1 {
+            Duration::ZERO
+        } else {
+            let exponent = u32::try_from(attempt - 1).unwrap_or(u32::MAX);
+            Duration::from_millis(2u64.saturating_pow(exponent)).min(Duration::from_secs(4))
+        }
+    }
+}
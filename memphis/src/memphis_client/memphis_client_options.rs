@@ -0,0 +1,183 @@
+use std::fmt::{Debug, Formatter};
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// Memphis Client Options
+///
+/// # Example
+/// ```rust,no_run
+/// use memphis_rust_community::memphis_client::{MemphisClient, MemphisClientOptions};
+/// use std::time::Duration;
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let options = MemphisClientOptions::new("memphis-0.example.com:6666", "root")
+///         .with_server("memphis-1.example.com:6666")
+///         .with_credentials_file("/etc/memphis/root.creds")
+///         .with_root_certificate("/etc/memphis/ca.pem")
+///         .with_client_certificate("/etc/memphis/client.pem", "/etc/memphis/client-key.pem")
+///         .with_request_timeout(Some(Duration::from_secs(10)));
+///
+///     let client = MemphisClient::connect(options).await.unwrap();
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct MemphisClientOptions {
+    /// The seed servers, the client connects to the first one which is available.
+    pub servers: Vec<String>,
+    /// The name of the Memphis user. Used for the requests to Memphis, whatever the credentials are.
+    pub username: String,
+    /// The account id of the Memphis account (Only used in cloud version).
+    pub account_id: String,
+    pub credentials: MemphisCredentials,
+    pub tls: MemphisTlsOptions,
+    pub connection_timeout: Duration,
+    pub ping_interval: Duration,
+    pub subscription_capacity: usize,
+    /// The timeout of requests to the broker, `None` waits forever.
+    pub request_timeout: Option<Duration>,
+}
+
+/// How the client authenticates against the broker.
+#[derive(Clone)]
+pub enum MemphisCredentials {
+    /// The password of the Memphis user.
+    Password(String),
+    /// A connection token.
+    Token(String),
+    /// The seed of an nkey.
+    Nkey(String),
+    /// The path to a `.creds` file, containing a JWT and the nkey seed to sign it.
+    CredentialsFile(PathBuf),
+    /// The contents of a `.creds` file.
+    Credentials(String),
+}
+
+/// Does not print the secrets.
+impl Debug for MemphisCredentials {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MemphisCredentials::Password(_) => f.write_str("Password(..)"),
+            MemphisCredentials::Token(_) => f.write_str("Token(..)"),
+            MemphisCredentials::Nkey(_) => f.write_str("Nkey(..)"),
+            MemphisCredentials::CredentialsFile(path) => {
+                f.debug_tuple("CredentialsFile").field(path).finish()
+            }
+            MemphisCredentials::Credentials(_) => f.write_str("Credentials(..)"),
+        }
+    }
+}
+
+/// The TLS settings of the connection. See [MemphisClientOptions].
+#[derive(Debug, Clone, Default)]
+pub struct MemphisTlsOptions {
+    /// PEM files with the certificates of custom root CAs.
+    pub root_certificates: Vec<PathBuf>,
+    /// The PEM files of the client certificate and its private key, used for mTLS.
+    pub client_certificate: Option<(PathBuf, PathBuf)>,
+    /// Refuses to connect without TLS, even if no certificate is configured.
+    pub require_tls: bool,
+}
+
+impl Default for MemphisClientOptions {
+    fn default() -> Self {
+        MemphisClientOptions {
+            servers: vec![String::from("localhost:6666")],
+            username: String::from("root"),
+            account_id: String::from("1"),
+            credentials: MemphisCredentials::Password(String::from("memphis")),
+            tls: MemphisTlsOptions::default(),
+            connection_timeout: Duration::from_secs(5),
+            ping_interval: Duration::from_secs(1),
+            subscription_capacity: 1024,
+            request_timeout: Some(Duration::from_secs(5)),
+        }
+    }
+}
+
+impl MemphisClientOptions {
+    pub fn new(memphis_host: &str, username: &str) -> Self {
+        MemphisClientOptions {
+            servers: vec![memphis_host.to_string()],
+            username: username.to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// Adds a seed server.
+    pub fn with_server(mut self, server: &str) -> Self {
+        self.servers.push(server.to_string());
+        self
+    }
+
+    pub fn with_account_id(mut self, account_id: &str) -> Self {
+        self.account_id = account_id.to_string();
+        self
+    }
+
+    pub fn with_password(mut self, password: &str) -> Self {
+        self.credentials = MemphisCredentials::Password(password.to_string());
+        self
+    }
+
+    pub fn with_token(mut self, token: &str) -> Self {
+        self.credentials = MemphisCredentials::Token(token.to_string());
+        self
+    }
+
+    pub fn with_nkey(mut self, seed: &str) -> Self {
+        self.credentials = MemphisCredentials::Nkey(seed.to_string());
+        self
+    }
+
+    pub fn with_credentials_file(mut self, path: impl Into<PathBuf>) -> Self {
+        self.credentials = MemphisCredentials::CredentialsFile(path.into());
+        self
+    }
+
+    pub fn with_credentials(mut self, credentials: &str) -> Self {
+        self.credentials = MemphisCredentials::Credentials(credentials.to_string());
+        self
+    }
+
+    /// Adds a root CA which is trusted in addition to the system roots.
+    pub fn with_root_certificate(mut self, path: impl Into<PathBuf>) -> Self {
+        self.tls.root_certificates.push(path.into());
+        self
+    }
+
+    /// Sets the client certificate and its private key, for brokers requiring mTLS.
+    pub fn with_client_certificate(
+        mut self,
+        cert: impl Into<PathBuf>,
+        key: impl Into<PathBuf>,
+    ) -> Self {
+        self.tls.client_certificate = Some((cert.into(), key.into()));
+        self
+    }
+
+    pub fn with_require_tls(mut self, require_tls: bool) -> Self {
+        self.tls.require_tls = require_tls;
+        self
+    }
+
+    pub fn with_connection_timeout(mut self, connection_timeout: Duration) -> Self {
+        self.connection_timeout = connection_timeout;
+        self
+    }
+
+    pub fn with_ping_interval(mut self, ping_interval: Duration) -> Self {
+        self.ping_interval = ping_interval;
+        self
+    }
+
+    pub fn with_subscription_capacity(mut self, subscription_capacity: usize) -> Self {
+        self.subscription_capacity = subscription_capacity;
+        self
+    }
+
+    pub fn with_request_timeout(mut self, request_timeout: Option<Duration>) -> Self {
+        self.request_timeout = request_timeout;
+        self
+    }
+}
@@ -0,0 +1,111 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+
+use async_nats::Client;
+use futures_util::future::join_all;
+use log::error;
+
+use crate::memphis_client::MemphisClient;
+use crate::RequestError;
+
+/// A producer or consumer which is registered again after a reconnect,
+/// and deregistered from Memphis when the client shuts down.
+///
+/// Implementations must not hold a [MemphisClient], which would keep the client alive.
+#[async_trait::async_trait]
+pub(crate) trait Resource: Send + Sync {
+    /// Stops receiving new work.
+    fn stop(&self) {}
+
+    /// Waits until the work in flight is finished.
+    async fn wait_idle(&self) {}
+
+    /// Sends the creation request to Memphis again and applies the refreshed partitions.
+    async fn reregister(&self, connection: &Client) -> Result<(), RequestError>;
+
+    /// Sends the destruction request to Memphis.
+    async fn destroy(&self, client: &MemphisClient) -> Result<(), RequestError>;
+}
+
+/// The producers and consumers created with a client, which were not destroyed yet.
+/// See [shutdown](MemphisClient::shutdown).
+#[derive(Clone, Default)]
+pub(crate) struct ResourceRegistry {
+    resources: Arc<Mutex<HashMap<u64, Arc<dyn Resource>>>>,
+    next_id: Arc<AtomicU64>,
+}
+
+impl ResourceRegistry {
+    /// Returns the entry of the resource, which deregisters it when dropped.
+    pub(crate) fn register(&self, resource: Arc<dyn Resource>) -> RegistryEntry {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        self.resources().insert(id, resource);
+        RegistryEntry {
+            registry: self.clone(),
+            id,
+            kept: AtomicBool::new(false),
+        }
+    }
+
+    fn deregister(&self, id: u64) {
+        self.resources().remove(&id);
+    }
+
+    /// Removes and returns all registered resources.
+    pub(crate) fn take_all(&self) -> Vec<Arc<dyn Resource>> {
+        self.resources()
+            .drain()
+            .map(|(_, resource)| resource)
+            .collect()
+    }
+
+    /// Registers all resources in Memphis again, e.g. after the broker was restarted.
+    pub(crate) async fn reregister_all(&self, connection: &Client) {
+        let resources: Vec<_> = self.resources().values().cloned().collect();
+        let results = join_all(
+            resources
+                .iter()
+                .map(|resource| resource.reregister(connection)),
+        )
+        .await;
+
+        for e in results.into_iter().filter_map(Result::err) {
+            error!("Error registering producer or consumer again. {}", e);
+        }
+    }
+
+    fn resources(&self) -> MutexGuard<'_, HashMap<u64, Arc<dyn Resource>>> {
+        self.resources
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// The entry of a producer or consumer in the [ResourceRegistry].
+/// The resource is deregistered when the entry is dropped, unless it was [kept](RegistryEntry::keep).
+pub(crate) struct RegistryEntry {
+    registry: ResourceRegistry,
+    id: u64,
+    kept: AtomicBool,
+}
+
+impl RegistryEntry {
+    /// Deregisters the resource right away, e.g. after it was destroyed.
+    pub(crate) fn deregister(&self) {
+        self.registry.deregister(self.id);
+    }
+
+    /// Keeps the resource registered after the entry is dropped, so it is destroyed on shutdown.
+    pub(crate) fn keep(&self) {
+        self.kept.store(true, Ordering::Relaxed);
+    }
+}
+
+impl Drop for RegistryEntry {
+    fn drop(&mut self) {
+        if !self.kept.load(Ordering::Relaxed) {
+            self.deregister();
+        }
+    }
+}
@@ -0,0 +1,99 @@
+use async_nats::{ConnectError, ConnectErrorKind};
+use thiserror::Error;
+
+use crate::codec::CodecError;
+#[cfg(feature = "consumers")]
+use crate::consumer::{ConsumerError, FetchError, MessageError};
+#[cfg(feature = "producers")]
+use crate::producer::ProducerError;
+use crate::request_error::RequestError;
+#[cfg(feature = "schemaverse")]
+use crate::schemaverse::schema::SchemaValidationError;
+
+/// Any error returned by this crate, so applications can use a single error type.
+///
+/// # Example
+/// ```rust,no_run
+/// use memphis_rust_community::memphis_client::MemphisClient;
+/// use memphis_rust_community::station::MemphisStationsOptions;
+/// use memphis_rust_community::MemphisError;
+///
+/// async fn create_station() -> Result<(), MemphisError> {
+///     let client = MemphisClient::new("localhost:6666", "root", "memphis", None).await?;
+///     let _station = client.create_station(MemphisStationsOptions::new("my-station")).await?;
+///     Ok(())
+/// }
+/// ```
+#[derive(Error, Debug)]
+pub enum MemphisError {
+    #[error("ConnectError: {0}")]
+    ConnectError(#[from] ConnectError),
+
+    #[error("RequestError: {0}")]
+    RequestError(#[from] RequestError),
+
+    #[cfg(feature = "producers")]
+    #[error("ProducerError: {0}")]
+    ProducerError(#[from] ProducerError),
+
+    #[cfg(feature = "consumers")]
+    #[error("ConsumerError: {0}")]
+    ConsumerError(#[from] ConsumerError),
+
+    #[cfg(feature = "consumers")]
+    #[error("{0}")]
+    FetchError(#[from] FetchError),
+
+    #[cfg(feature = "consumers")]
+    #[error("MessageError: {0}")]
+    MessageError(#[from] MessageError),
+
+    #[cfg(feature = "schemaverse")]
+    #[error("SchemaValidationError: {0}")]
+    SchemaValidationError(#[from] SchemaValidationError),
+
+    #[error("CodecError: {0}")]
+    CodecError(#[from] CodecError),
+
+    /// Returned by the methods starting to consume, like [consume](crate::consumer::MemphisConsumer::consume).
+    #[error("NatsError: {0}")]
+    NatsError(#[from] async_nats::Error),
+}
+
+impl MemphisError {
+    /// Whether the operation may succeed if it is tried again, e.g. after the connection was re-established.
+    /// Invalid arguments, missing entities and failed validations are not retryable.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            MemphisError::ConnectError(e) => matches!(
+                e.kind(),
+                ConnectErrorKind::Dns | ConnectErrorKind::TimedOut | ConnectErrorKind::Io
+            ),
+            MemphisError::RequestError(e) => e.is_retryable(),
+            #[cfg(feature = "producers")]
+            MemphisError::ProducerError(e) => match e {
+                ProducerError::NatsPublishError(_) | ProducerError::PartitionUnavailable => true,
+                #[cfg(feature = "schemaverse")]
+                ProducerError::SchemaValidationError(e) => e.is_retryable(),
+                _ => false,
+            },
+            #[cfg(feature = "consumers")]
+            MemphisError::ConsumerError(e) => match e {
+                ConsumerError::RequestError(e) => e.is_retryable(),
+                ConsumerError::InvalidSequence | ConsumerError::InvalidResponse(_) => false,
+            },
+            #[cfg(feature = "consumers")]
+            MemphisError::FetchError(_) => true,
+            #[cfg(feature = "consumers")]
+            MemphisError::MessageError(e) => match e {
+                MessageError::AckError(_) => true,
+                MessageError::RequestError(e) => e.is_retryable(),
+                MessageError::MetadataError(_) => false,
+            },
+            #[cfg(feature = "schemaverse")]
+            MemphisError::SchemaValidationError(e) => e.is_retryable(),
+            MemphisError::CodecError(_) => false,
+            MemphisError::NatsError(_) => true,
+        }
+    }
+}
@@ -2,12 +2,16 @@ pub(crate) use consumer::*;
 #[cfg(feature = "schemaverse")]
 pub(crate) use notification::*;
 pub(crate) use producer::*;
+#[cfg(feature = "schemaverse")]
+pub(crate) use schema::*;
 pub(crate) use station::*;
 
 mod consumer;
 #[cfg(feature = "schemaverse")]
 mod notification;
 mod producer;
+#[cfg(feature = "schemaverse")]
+mod schema;
 mod station;
 
 pub(crate) mod pm_ack_msg;
@@ -0,0 +1,22 @@
+use serde::Serialize;
+
+#[derive(Debug, Serialize)]
+pub(crate) struct AttachSchemaRequest<'a> {
+    #[serde(rename = "name")]
+    pub(crate) schema_name: &'a str,
+
+    #[serde(rename = "station_name")]
+    pub(crate) station_name: &'a str,
+
+    #[serde(rename = "username")]
+    pub(crate) username: &'a str,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct DetachSchemaRequest<'a> {
+    #[serde(rename = "station_name")]
+    pub(crate) station_name: &'a str,
+
+    #[serde(rename = "username")]
+    pub(crate) username: &'a str,
+}
@@ -1,4 +1,8 @@
 pub(crate) use consumer::*;
 pub(crate) use producer::*;
+#[cfg(feature = "schemaverse")]
+pub(crate) use schema::*;
 mod consumer;
 mod producer;
+#[cfg(feature = "schemaverse")]
+mod schema;
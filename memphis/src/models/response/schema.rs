@@ -0,0 +1,14 @@
+use serde::{Deserialize, Serialize};
+
+use crate::models::response::SchemaUpdate;
+
+pub(crate) const SCHEMA_UPDATE_TYPE_INIT: u32 = 1;
+pub(crate) const SCHEMA_UPDATE_TYPE_DROP: u32 = 2;
+
+/// Message published by Memphis on `$memphis_schema_updates_<station>`.
+#[derive(Serialize, Deserialize, Debug)]
+pub(crate) struct SchemaUpdateMessage {
+    #[serde(rename = "type")]
+    pub(crate) update_type: u32,
+    pub(crate) init: Option<SchemaUpdate>,
+}
@@ -10,6 +10,8 @@ pub struct ComposableMessage {
     #[serde(serialize_with = "hex::serde::serialize")]
     pub(crate) payload: Bytes,
     pub(crate) msg_id: Option<String>,
+    #[serde(skip)]
+    pub(crate) partition_key: Option<String>,
 }
 
 impl ComposableMessage {
@@ -31,6 +33,15 @@ impl ComposableMessage {
         self.msg_id = Some(msg_id.into());
         self
     }
+
+    /// Sets the partition key of the message.
+    ///
+    /// Messages with the same partition key are always produced to the same partition,
+    /// which keeps their order. See [get_partition_from_key](crate::producer::get_partition_from_key).
+    pub fn with_partition_key(mut self, partition_key: impl Into<String>) -> Self {
+        self.partition_key = Some(partition_key.into());
+        self
+    }
 }
 
 impl From<ComposableMessage> for Request {
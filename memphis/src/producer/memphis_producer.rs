@@ -1,26 +1,52 @@
+use std::sync::{Arc, Mutex, PoisonError, RwLock, RwLockReadGuard};
+
 use async_nats::jetstream::context::PublishAckFuture;
+use async_nats::Client;
 use log::{error, info};
 
+#[cfg(feature = "schemaverse")]
+use log::warn;
+
 #[cfg(feature = "schemaverse")]
 use crate::constants::memphis_constants::MemphisNotificationType;
 
 use crate::constants::memphis_constants::{MemphisHeaders, MemphisSpecialStation};
 use crate::helper::memphis_util::sanitize_name;
-use crate::helper::partition_iterator::PartitionIterator;
+use crate::memphis_client::{send_request, MemphisClient, RegistryEntry, Resource};
 use crate::models::request::{CreateProducerRequest, DestroyProducerRequest};
 use crate::models::response::CreateProducerResponse;
 #[cfg(feature = "schemaverse")]
 use crate::producer::dls_message::{DlsMessage, DlsMessageProducer};
-use crate::producer::{ComposableMessage, MemphisProducerOptions, ProducerError};
+use crate::producer::{
+    get_partition_from_key, ComposableMessage, MemphisProducerOptions, PartitionStrategy,
+    ProducerError,
+};
 #[cfg(feature = "schemaverse")]
 use crate::schemaverse::schema::SchemaValidationError;
+#[cfg(feature = "schemaverse")]
+use crate::schemaverse::schema_updates::SchemaSubscription;
 use crate::station::MemphisStation;
 use crate::RequestError;
 
+/// Clones share the registration and the partition strategy of the producer.
+/// The producer is deregistered once the last clone was dropped.
+#[derive(Clone)]
 pub struct MemphisProducer {
     station: MemphisStation,
-    options: MemphisProducerOptions,
-    partitions_iterator: Option<PartitionIterator<u32>>,
+    producer_name: String,
+    partition_strategy: Arc<Mutex<Box<dyn PartitionStrategy>>>,
+    registration: Arc<ProducerRegistration>,
+    /// The entry in the resource registry of the client, see [shutdown](MemphisClient::shutdown).
+    registry_entry: Arc<RegistryEntry>,
+    /// Whether messages failing the schema validation are sent to the DLS, as configured on the server.
+    #[cfg(feature = "schemaverse")]
+    schemaverse_to_dls: bool,
+    /// Whether a notification is sent if the schema validation fails, as configured on the server.
+    #[cfg(feature = "schemaverse")]
+    send_notification: bool,
+    /// Keeps the validator of the station up to date while the producer lives.
+    #[cfg(feature = "schemaverse")]
+    _schema_subscription: Arc<SchemaSubscription>,
 }
 
 impl MemphisProducer {
@@ -30,6 +56,24 @@ impl MemphisProducer {
     ) -> Result<Self, RequestError> {
         sanitize_name(&mut options.producer_name, options.generate_unique_suffix);
 
+        Self::create(station, options).await
+    }
+
+    async fn create(
+        station: MemphisStation,
+        options: MemphisProducerOptions,
+    ) -> Result<Self, RequestError> {
+        // Listen to schema updates before creating the producer, so no update gets lost.
+        #[cfg(feature = "schemaverse")]
+        let schema_subscription = station
+            .memphis_client
+            .schema_updates
+            .add_producer(
+                station.memphis_client.get_broker_connection(),
+                &station.get_internal_name(None),
+            )
+            .await?;
+
         let req = CreateProducerRequest {
             producer_name: &options.producer_name,
             station_name: &station.options.station_name,
@@ -38,10 +82,19 @@ impl MemphisProducer {
             req_version: 2,
             username: &station.memphis_client.username,
         };
+        let registration = Arc::new(ProducerRegistration {
+            producer_name: options.producer_name.clone(),
+            station_name: station.options.station_name.clone(),
+            create_request: serde_json::to_value(&req)?,
+            partitions_list: RwLock::new(None),
+        });
 
         let res = match station
             .memphis_client
-            .send_internal_request(&req, MemphisSpecialStation::ProducerCreations)
+            .send_internal_request(
+                &registration.create_request,
+                MemphisSpecialStation::ProducerCreations,
+            )
             .await
         {
             Ok(res) => res,
@@ -52,32 +105,75 @@ impl MemphisProducer {
         };
 
         let res = std::str::from_utf8(&res.payload)
-            .map_err(|e| RequestError::MemphisError(e.to_string()))?;
+            .map_err(|e| RequestError::InvalidResponse(e.to_string()))?;
 
         let producer = match serde_json::from_str::<CreateProducerResponse>(res) {
             Ok(x) => {
-                let partitions_iterator = if let Some(partitions) = x.partitions_update {
-                    Some(PartitionIterator::new(partitions.partitions_list))
-                } else {
-                    None
-                };
+                #[cfg(feature = "schemaverse")]
+                match station.install_schema(&x.schema_update).await {
+                    Ok(()) => {}
+                    Err(SchemaValidationError::UnsupportedSchemaType(schema_type)) => {
+                        warn!(
+                            "Schema '{}' of station '{}' has the type '{}', which is not supported. Producing without validation.",
+                            &x.schema_update.schema_name, &station.options.station_name, schema_type
+                        );
+                    }
+                    Err(e) => {
+                        error!(
+                            "Error creating validator for schema '{}': {}",
+                            &x.schema_update.schema_name, e
+                        );
+                        // The producer was already created in Memphis.
+                        let _ = registration
+                            .send_destroy_request(&station.memphis_client)
+                            .await;
+                        return Err(RequestError::SchemaError(Box::new(e)));
+                    }
+                }
 
+                registration.set_partitions_list(x.partitions_update.map(|p| p.partitions_list));
                 Self {
+                    registry_entry: Arc::new(
+                        station
+                            .memphis_client
+                            .registry
+                            .register(registration.clone()),
+                    ),
                     station,
-                    options,
-                    partitions_iterator,
+                    producer_name: options.producer_name,
+                    partition_strategy: Arc::new(Mutex::new(options.partition_strategy)),
+                    registration,
+                    #[cfg(feature = "schemaverse")]
+                    schemaverse_to_dls: x.schemaverse_to_dls,
+                    #[cfg(feature = "schemaverse")]
+                    send_notification: x.send_notification,
+                    #[cfg(feature = "schemaverse")]
+                    _schema_subscription: schema_subscription,
                 }
             }
             Err(e) => {
                 if res.is_empty() {
                     Self {
+                        registry_entry: Arc::new(
+                            station
+                                .memphis_client
+                                .registry
+                                .register(registration.clone()),
+                        ),
+                        #[cfg(feature = "schemaverse")]
+                        schemaverse_to_dls: station.options.send_schema_failed_msg_to_dls,
+                        #[cfg(feature = "schemaverse")]
+                        send_notification: true,
+                        #[cfg(feature = "schemaverse")]
+                        _schema_subscription: schema_subscription,
                         station,
-                        options,
-                        partitions_iterator: None,
+                        producer_name: options.producer_name,
+                        partition_strategy: Arc::new(Mutex::new(options.partition_strategy)),
+                        registration,
                     }
                 } else {
                     error!("Error creating producer: {}", e);
-                    return Err(RequestError::MemphisError(e.to_string()));
+                    return Err(RequestError::InvalidResponse(e.to_string()));
                 }
             }
         };
@@ -95,13 +191,13 @@ impl MemphisProducer {
         if message.payload.is_empty() {
             return Err(ProducerError::PayloadEmpty);
         }
-        if let Some(partition_list) = &self.partitions_iterator {
+        if let Some(partitions_list) = &*self.registration.partitions_list() {
             match partition {
                 None => {
                     return Err(ProducerError::PartitionRequired);
                 }
                 Some(partition) => {
-                    if !partition_list.get_partition_list().contains(&partition) {
+                    if !partitions_list.contains(&partition) {
                         return Err(ProducerError::PartitionNotValid(partition));
                     }
                 }
@@ -110,7 +206,7 @@ impl MemphisProducer {
 
         message.headers.insert(
             MemphisHeaders::MemphisProducedBy,
-            self.options.producer_name.as_str(),
+            self.producer_name.as_str(),
         );
         message.headers.insert(
             MemphisHeaders::MemphisConnectionId,
@@ -140,7 +236,9 @@ impl MemphisProducer {
     }
 
     /// Produces a message to the station.
-    /// If the station has partitions, the message will round-robin between the partitions.
+    /// If the station has partitions, the partition is chosen by the
+    /// [PartitionStrategy](crate::producer::PartitionStrategy) of the producer, which defaults to round-robin,
+    /// unless a partition key was set using [with_partition_key](ComposableMessage::with_partition_key).
     ///
     /// To ensure that the message is produced to a specific partition, use [produce_to_partition](MemphisProducer::produce_to_partition).
     ///
@@ -150,46 +248,72 @@ impl MemphisProducer {
         &mut self,
         message: ComposableMessage,
     ) -> Result<PublishAckFuture, ProducerError> {
-        let ack_future = match &mut self.partitions_iterator {
-            None => self.produce_to_partition(None, message).await?,
-            Some(iterator) => {
-                let partition = if let Some(partition) = iterator.next() {
-                    partition
-                } else {
+        if let Some(partition_key) = message.partition_key.clone() {
+            return self.produce_with_key(&partition_key, message).await;
+        }
+
+        let partition = match &*self.registration.partitions_list() {
+            None => None,
+            Some(partitions_list) => {
+                if partitions_list.is_empty() {
+                    return Err(ProducerError::PartitionUnavailable);
+                }
+                let partition = self
+                    .partition_strategy
+                    .lock()
+                    .unwrap_or_else(PoisonError::into_inner)
+                    .select_partition(partitions_list, &message);
+                if partition.is_none() {
                     return Err(ProducerError::PartitionUnavailable);
-                };
-                self.produce_to_partition(Some(partition), message).await?
+                }
+                partition
             }
         };
-        Ok(ack_future)
+        self.produce_to_partition(partition, message).await
     }
 
-    pub async fn destroy(self) -> Result<(), RequestError> {
-        let req = DestroyProducerRequest {
-            producer_name: &self.options.producer_name,
-            station_name: &self.station.options.station_name,
-            connection_id: &self.station.memphis_client.connection_id,
-            username: &self.station.memphis_client.username,
-            req_version: 1,
+    /// Produces a message to the partition the given key is mapped to.
+    ///
+    /// All messages produced with the same key end up in the same partition and keep their order.
+    /// The key is hashed the same way the official Memphis SDKs do, see [get_partition_from_key].
+    ///
+    /// For more details, see [produce](MemphisProducer::produce).
+    pub async fn produce_with_key(
+        &self,
+        key: &str,
+        message: ComposableMessage,
+    ) -> Result<PublishAckFuture, ProducerError> {
+        let partition = match &*self.registration.partitions_list() {
+            None => None,
+            Some(partitions_list) => {
+                let partition = get_partition_from_key(key, partitions_list);
+                if partition.is_none() {
+                    return Err(ProducerError::PartitionUnavailable);
+                }
+                partition
+            }
         };
+        self.produce_to_partition(partition, message).await
+    }
 
+    /// Destroys the producer on the server, which also ends its clones.
+    pub async fn destroy(self) -> Result<(), RequestError> {
         if let Err(e) = self
-            .station
-            .memphis_client
-            .send_internal_request(&req, MemphisSpecialStation::ProducerDestructions)
+            .registration
+            .destroy(&self.station.memphis_client)
             .await
         {
-            error!("Error destroying producer. {}", &e);
+            // Stays registered, so it is destroyed again on shutdown.
+            self.registry_entry.keep();
             return Err(e);
         }
-
-        info!("Destroyed producer {}.", &self.options.producer_name);
-
+        // The remaining clones must not create it again after a reconnect.
+        self.registry_entry.deregister();
         Ok(())
     }
 
     pub fn get_name(&self) -> String {
-        self.options.producer_name.clone()
+        self.producer_name.clone()
     }
 }
 
@@ -199,14 +323,16 @@ impl MemphisProducer {
         &self,
         message: &ComposableMessage,
     ) -> Result<(), SchemaValidationError> {
-        let Some(schema_validator) = self.station.schema.clone() else {
+        let Some(schema_validator) = self.station.schema.read().await.clone() else {
             return Ok(());
         };
 
         if let Err(e) = schema_validator.validate(&message.payload) {
-            self.send_notification(message, &e).await?;
+            if self.send_notification {
+                self.send_notification(message, &e).await?;
+            }
 
-            if self.station.options.send_schema_failed_msg_to_dls {
+            if self.schemaverse_to_dls {
                 self.send_message_to_dls(message, &e).await?;
             }
 
@@ -229,7 +355,7 @@ impl MemphisProducer {
                 "Schema validation has failed ",
                 &format!(
                     "Station {}\nProducer: {}\nError: {}",
-                    &self.station.options.station_name, &self.options.producer_name, &e
+                    &self.station.options.station_name, &self.producer_name, &e
                 ),
                 std::str::from_utf8(&message.payload).unwrap_or("no valid utf8 supplied"),
             )
@@ -245,7 +371,7 @@ impl MemphisProducer {
         let req = DlsMessage {
             station_name: &self.station.options.station_name,
             producer: DlsMessageProducer {
-                name: &self.options.producer_name,
+                name: &self.producer_name,
                 connection_id: &self.station.memphis_client.connection_id,
             },
             message,
@@ -259,3 +385,77 @@ impl MemphisProducer {
         Ok(())
     }
 }
+
+/// The state needed to register the producer again after a reconnect, and to destroy it when the client shuts down.
+struct ProducerRegistration {
+    producer_name: String,
+    station_name: String,
+    /// The request the producer was created with.
+    create_request: serde_json::Value,
+    /// The partitions of the station, `None` if the broker does not support partitions.
+    partitions_list: RwLock<Option<Vec<u32>>>,
+}
+
+impl ProducerRegistration {
+    fn partitions_list(&self) -> RwLockReadGuard<'_, Option<Vec<u32>>> {
+        self.partitions_list
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn set_partitions_list(&self, partitions_list: Option<Vec<u32>>) {
+        *self
+            .partitions_list
+            .write()
+            .unwrap_or_else(PoisonError::into_inner) = partitions_list;
+    }
+
+    async fn send_destroy_request(&self, client: &MemphisClient) -> Result<(), RequestError> {
+        let req = DestroyProducerRequest {
+            producer_name: &self.producer_name,
+            station_name: &self.station_name,
+            connection_id: &client.connection_id,
+            username: &client.username,
+            req_version: 1,
+        };
+
+        if let Err(e) = client
+            .send_internal_request(&req, MemphisSpecialStation::ProducerDestructions)
+            .await
+        {
+            error!("Error destroying producer. {}", &e);
+            return Err(e);
+        }
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl Resource for ProducerRegistration {
+    async fn reregister(&self, connection: &Client) -> Result<(), RequestError> {
+        let res = send_request(
+            connection,
+            &self.create_request,
+            MemphisSpecialStation::ProducerCreations,
+        )
+        .await?;
+
+        // Brokers without partitions send an empty response.
+        if !res.payload.is_empty() {
+            let res = serde_json::from_slice::<CreateProducerResponse>(&res.payload)?;
+            self.set_partitions_list(res.partitions_update.map(|p| p.partitions_list));
+        }
+
+        info!("Registered producer {} again.", &self.producer_name);
+
+        Ok(())
+    }
+
+    async fn destroy(&self, client: &MemphisClient) -> Result<(), RequestError> {
+        self.send_destroy_request(client).await?;
+
+        info!("Destroyed producer {}.", &self.producer_name);
+
+        Ok(())
+    }
+}
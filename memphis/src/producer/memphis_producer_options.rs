@@ -1,6 +1,10 @@
+use crate::producer::{PartitionStrategy, RoundRobinStrategy};
+
 pub struct MemphisProducerOptions {
     pub producer_name: String,
     pub generate_unique_suffix: bool,
+    /// Decides which partition a message is produced to. Defaults to [RoundRobinStrategy].
+    pub partition_strategy: Box<dyn PartitionStrategy>,
 }
 
 impl Default for MemphisProducerOptions {
@@ -8,6 +12,7 @@ impl Default for MemphisProducerOptions {
         Self {
             producer_name: "Default_Producer_name".to_string(),
             generate_unique_suffix: true,
+            partition_strategy: Box::new(RoundRobinStrategy::new()),
         }
     }
 }
@@ -24,4 +29,12 @@ impl MemphisProducerOptions {
         self.generate_unique_suffix = generate_unique_suffix;
         self
     }
+
+    pub fn with_partition_strategy(
+        mut self,
+        partition_strategy: impl PartitionStrategy + 'static,
+    ) -> Self {
+        self.partition_strategy = Box::new(partition_strategy);
+        self
+    }
 }
@@ -1,10 +1,17 @@
 mod composable_message;
+#[cfg(feature = "schemaverse")]
 mod dls_message;
 mod memphis_producer;
 mod memphis_producer_options;
+mod partition_key;
+mod partition_strategy;
 mod producer_error;
+mod typed_producer;
 
 pub use composable_message::*;
 pub use memphis_producer::*;
 pub use memphis_producer_options::*;
+pub use partition_key::*;
+pub use partition_strategy::*;
 pub use producer_error::*;
+pub use typed_producer::*;
@@ -0,0 +1,30 @@
+use std::io::Cursor;
+
+use murmur3::murmur3_32;
+
+/// Seed used by every official Memphis SDK when hashing partition keys.
+const PARTITION_KEY_SEED: u32 = 31;
+
+/// Returns the partition a message with the given key will be produced to.
+///
+/// The key is hashed with murmur3 (x86, 32 bit) using the same seed as the official Memphis SDKs,
+/// so a key is always mapped to the same partition regardless of the SDK producing it.
+///
+/// Returns `None` if `partitions` is empty.
+///
+/// # Example
+/// ```rust
+/// use memphis_rust_community::producer::get_partition_from_key;
+///
+/// let partition = get_partition_from_key("customer-1", &[1, 2, 3, 4, 5, 6, 7, 8, 9, 10]);
+/// assert_eq!(partition, Some(8));
+/// ```
+pub fn get_partition_from_key(key: &str, partitions: &[u32]) -> Option<u32> {
+    if partitions.is_empty() {
+        return None;
+    }
+
+    // Reading from a Cursor can not fail.
+    let hash = murmur3_32(&mut Cursor::new(key.as_bytes()), PARTITION_KEY_SEED).unwrap_or(0);
+    partitions.get(hash as usize % partitions.len()).copied()
+}
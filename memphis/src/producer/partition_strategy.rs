@@ -0,0 +1,206 @@
+use std::collections::HashMap;
+
+use rand::Rng;
+
+use crate::producer::{get_partition_from_key, ComposableMessage};
+
+/// Decides which partition a message is produced to.
+///
+/// The strategy is only consulted if the station has partitions and the message has no
+/// partition key set, see [with_partition_key](ComposableMessage::with_partition_key).
+///
+/// Closures with the signature `FnMut(&[u32], &ComposableMessage) -> Option<u32>` implement this trait,
+/// so custom strategies can be written inline.
+///
+/// # Example
+/// ```rust
+/// use memphis_rust_community::producer::{ComposableMessage, MemphisProducerOptions};
+///
+/// let options = MemphisProducerOptions::new("my-producer").with_partition_strategy(
+///     |partitions: &[u32], _message: &ComposableMessage| partitions.first().copied(),
+/// );
+/// ```
+pub trait PartitionStrategy: Send + Sync {
+    /// Returns the partition the message should be produced to.
+    ///
+    /// # Arguments
+    /// * `partitions` - The partitions of the station. Never empty.
+    /// * `message` - The message that is about to be produced.
+    ///
+    /// Returning `None` fails the produce call with [PartitionUnavailable](crate::producer::ProducerError::PartitionUnavailable).
+    fn select_partition(&mut self, partitions: &[u32], message: &ComposableMessage) -> Option<u32>;
+}
+
+impl<F> PartitionStrategy for F
+where
+    F: FnMut(&[u32], &ComposableMessage) -> Option<u32> + Send + Sync,
+{
+    fn select_partition(&mut self, partitions: &[u32], message: &ComposableMessage) -> Option<u32> {
+        self(partitions, message)
+    }
+}
+
+/// Cycles through all partitions one after another. This is the default strategy.
+#[derive(Debug, Default)]
+pub struct RoundRobinStrategy {
+    index: usize,
+}
+
+impl RoundRobinStrategy {
+    pub fn new() -> Self {
+        Default::default()
+    }
+}
+
+impl PartitionStrategy for RoundRobinStrategy {
+    fn select_partition(
+        &mut self,
+        partitions: &[u32],
+        _message: &ComposableMessage,
+    ) -> Option<u32> {
+        self.index = (self.index + 1) % partitions.len();
+        partitions.get(self.index).copied()
+    }
+}
+
+/// Picks a random partition for every message.
+#[derive(Debug, Default)]
+pub struct RandomStrategy;
+
+impl RandomStrategy {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl PartitionStrategy for RandomStrategy {
+    fn select_partition(
+        &mut self,
+        partitions: &[u32],
+        _message: &ComposableMessage,
+    ) -> Option<u32> {
+        let index = rand::thread_rng().gen_range(0..partitions.len());
+        partitions.get(index).copied()
+    }
+}
+
+/// Sticks to a randomly chosen partition and only switches to another one after
+/// `batch_size` messages have been produced.
+///
+/// This keeps consecutive messages together while still spreading the load over time.
+#[derive(Debug)]
+pub struct StickyStrategy {
+    batch_size: usize,
+    produced: usize,
+    current: Option<u32>,
+}
+
+impl StickyStrategy {
+    /// Creates a strategy that never switches the partition, as long as it is still available.
+    pub fn new() -> Self {
+        Self::with_batch_size(usize::MAX)
+    }
+
+    /// Creates a strategy that switches to a new random partition every `batch_size` messages.
+    pub fn with_batch_size(batch_size: usize) -> Self {
+        Self {
+            batch_size: batch_size.max(1),
+            produced: 0,
+            current: None,
+        }
+    }
+}
+
+impl Default for StickyStrategy {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PartitionStrategy for StickyStrategy {
+    fn select_partition(&mut self, partitions: &[u32], message: &ComposableMessage) -> Option<u32> {
+        let current = self.current.filter(|p| partitions.contains(p));
+        let partition = match current {
+            Some(partition) if self.produced < self.batch_size => partition,
+            _ => {
+                self.produced = 0;
+                RandomStrategy.select_partition(partitions, message)?
+            }
+        };
+
+        self.produced += 1;
+        self.current = Some(partition);
+        Some(partition)
+    }
+}
+
+/// Picks the partition that has not been used for the longest time.
+///
+/// Unlike [RoundRobinStrategy] this keeps spreading messages evenly if the list of partitions changes.
+#[derive(Debug, Default)]
+pub struct LeastRecentlyUsedStrategy {
+    counter: u64,
+    last_used: HashMap<u32, u64>,
+    /// The partitions of the last call, so removed partitions are only forgotten when the list changes.
+    partitions: Vec<u32>,
+}
+
+impl LeastRecentlyUsedStrategy {
+    pub fn new() -> Self {
+        Default::default()
+    }
+}
+
+impl PartitionStrategy for LeastRecentlyUsedStrategy {
+    fn select_partition(
+        &mut self,
+        partitions: &[u32],
+        _message: &ComposableMessage,
+    ) -> Option<u32> {
+        let partition = partitions
+            .iter()
+            .copied()
+            .min_by_key(|p| self.last_used.get(p).copied().unwrap_or(0))?;
+
+        if self.partitions != partitions {
+            self.partitions = partitions.to_vec();
+            self.last_used.retain(|p, _| partitions.contains(p));
+        }
+
+        self.counter += 1;
+        self.last_used.insert(partition, self.counter);
+        Some(partition)
+    }
+}
+
+/// Uses the value of a message header as partition key.
+///
+/// The value is hashed the same way as a partition key, see [get_partition_from_key].
+/// Messages without the header are passed to the fallback strategy, which defaults to [RoundRobinStrategy].
+pub struct HeaderStrategy {
+    header_name: String,
+    fallback: Box<dyn PartitionStrategy>,
+}
+
+impl HeaderStrategy {
+    pub fn new(header_name: &str) -> Self {
+        Self {
+            header_name: header_name.to_string(),
+            fallback: Box::new(RoundRobinStrategy::new()),
+        }
+    }
+
+    pub fn with_fallback(mut self, fallback: impl PartitionStrategy + 'static) -> Self {
+        self.fallback = Box::new(fallback);
+        self
+    }
+}
+
+impl PartitionStrategy for HeaderStrategy {
+    fn select_partition(&mut self, partitions: &[u32], message: &ComposableMessage) -> Option<u32> {
+        match message.headers.get(self.header_name.as_str()) {
+            Some(value) => get_partition_from_key(value.as_str(), partitions),
+            None => self.fallback.select_partition(partitions, message),
+        }
+    }
+}
@@ -1,6 +1,8 @@
 use async_nats::jetstream::context::PublishError;
 use thiserror::Error;
 
+use crate::codec::CodecError;
+
 #[cfg(feature = "schemaverse")]
 use crate::schemaverse::schema::SchemaValidationError;
 
@@ -29,4 +31,8 @@ pub enum ProducerError {
 
     #[error("PartitionUnavailable")]
     PartitionUnavailable,
+
+    /// The value could not be encoded by the codec of a [TypedProducer](crate::producer::TypedProducer).
+    #[error("CodecError: {0}")]
+    CodecError(#[from] CodecError),
 }
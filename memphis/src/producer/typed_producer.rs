@@ -0,0 +1,117 @@
+use std::marker::PhantomData;
+
+use async_nats::jetstream::context::PublishAckFuture;
+use serde::Serialize;
+
+use crate::codec::{Codec, JsonCodec};
+use crate::constants::memphis_constants::MemphisHeaders;
+use crate::producer::{ComposableMessage, MemphisProducer, ProducerError};
+
+/// A producer that serializes values of type `T` with a [Codec] before producing them.
+///
+/// The content type of the codec is sent in the `content-type` header of every message.
+/// If a schema is attached to the station, the encoded payload is validated before it is produced.
+///
+/// # Example
+/// ```rust
+/// use memphis_rust_community::memphis_client::MemphisClient;
+/// use memphis_rust_community::producer::{MemphisProducerOptions, TypedProducer};
+/// use memphis_rust_community::station::MemphisStationsOptions;
+/// use serde::Serialize;
+///
+/// #[derive(Serialize)]
+/// struct Payment {
+///     id: u64,
+///     amount: f64,
+/// }
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let client = MemphisClient::new("localhost:6666", "root", "memphis", None).await.unwrap();
+///     let station = client.create_station(MemphisStationsOptions::new("payments")).await.unwrap();
+///
+///     let producer = station.create_producer(MemphisProducerOptions::new("payment-service")).await.unwrap();
+///     let mut producer = TypedProducer::<Payment>::new(producer);
+///
+///     producer.produce(&Payment { id: 1, amount: 9.99 }).await.unwrap();
+/// }
+/// ```
+pub struct TypedProducer<T: Serialize, C: Codec = JsonCodec> {
+    producer: MemphisProducer,
+    codec: C,
+    _marker: PhantomData<fn(&T)>,
+}
+
+impl<T: Serialize> TypedProducer<T> {
+    /// Creates a producer which encodes values as JSON.
+    pub fn new(producer: MemphisProducer) -> Self {
+        Self::with_codec(producer, JsonCodec)
+    }
+}
+
+impl<T: Serialize, C: Codec> TypedProducer<T, C> {
+    pub fn with_codec(producer: MemphisProducer, codec: C) -> Self {
+        Self {
+            producer,
+            codec,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Encodes the value and produces it to the station.
+    ///
+    /// For more details, see [produce](MemphisProducer::produce).
+    pub async fn produce(&mut self, value: &T) -> Result<PublishAckFuture, ProducerError> {
+        self.produce_message(value, ComposableMessage::new()).await
+    }
+
+    /// Encodes the value into the payload of the given message and produces it to the station.
+    ///
+    /// Use this to set headers, a message id or a partition key.
+    pub async fn produce_message(
+        &mut self,
+        value: &T,
+        message: ComposableMessage,
+    ) -> Result<PublishAckFuture, ProducerError> {
+        let message = self.encode(value, message)?;
+        self.producer.produce(message).await
+    }
+
+    /// Encodes the value into the payload of the given message and produces it to the given partition.
+    ///
+    /// For more details, see [produce_to_partition](MemphisProducer::produce_to_partition).
+    pub async fn produce_to_partition(
+        &self,
+        partition: Option<u32>,
+        value: &T,
+        message: ComposableMessage,
+    ) -> Result<PublishAckFuture, ProducerError> {
+        let message = self.encode(value, message)?;
+        self.producer.produce_to_partition(partition, message).await
+    }
+
+    fn encode(
+        &self,
+        value: &T,
+        message: ComposableMessage,
+    ) -> Result<ComposableMessage, ProducerError> {
+        let payload = self.codec.encode(value)?;
+
+        Ok(message
+            .with_header(MemphisHeaders::ContentType, self.codec.content_type())
+            .with_payload(payload))
+    }
+
+    pub fn codec(&self) -> &C {
+        &self.codec
+    }
+
+    /// Returns the underlying producer.
+    pub fn producer(&self) -> &MemphisProducer {
+        &self.producer
+    }
+
+    pub fn into_inner(self) -> MemphisProducer {
+        self.producer
+    }
+}
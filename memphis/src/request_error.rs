@@ -1,16 +1,220 @@
 use thiserror::Error;
 
+#[cfg(feature = "schemaverse")]
+use crate::schemaverse::schema::SchemaValidationError;
+
 #[derive(Error, Debug)]
 pub enum RequestError {
     #[error("NatsError: {0}")]
     NatsError(#[from] async_nats::Error),
 
-    #[error("MemphisError: {0}")]
-    MemphisError(String),
+    /// Memphis responded with a message which could not be parsed.
+    #[error("InvalidResponse: {0}")]
+    InvalidResponse(String),
+
+    /// Memphis rejected the request, see [MemphisServerError].
+    #[error("ServerError: {0}")]
+    ServerError(#[from] MemphisServerError),
 
     #[error("SerdeError: {0}")]
     SerdeError(#[from] serde_json::Error),
 
+    /// The schema of the station, sent by Memphis, could not be installed.
+    #[cfg(feature = "schemaverse")]
+    #[error("SchemaError: {0}")]
+    SchemaError(Box<SchemaValidationError>),
+
     #[error("Tried to send request whilst not connected")]
     NotConnected,
 }
+
+impl RequestError {
+    /// Whether sending the request again may succeed, e.g. after the connection was re-established.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            RequestError::NatsError(_) | RequestError::NotConnected => true,
+            RequestError::ServerError(e) => e.is_retryable(),
+            RequestError::InvalidResponse(_) | RequestError::SerdeError(_) => false,
+            #[cfg(feature = "schemaverse")]
+            RequestError::SchemaError(e) => e.is_retryable(),
+        }
+    }
+}
+
+/// The error Memphis responded with, classified by its message.
+/// Every variant contains the original message.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum MemphisServerError {
+    #[error("StationNotFound: {0}")]
+    StationNotFound(String),
+
+    /// The schema does not exist, or the station has no schema attached.
+    #[error("SchemaNotFound: {0}")]
+    SchemaNotFound(String),
+
+    /// A producer, consumer or other entity does not exist.
+    #[error("NotFound: {0}")]
+    NotFound(String),
+
+    #[error("AlreadyExists: {0}")]
+    AlreadyExists(String),
+
+    /// The user is not allowed to perform the request.
+    #[error("Unauthorized: {0}")]
+    Unauthorized(String),
+
+    /// The request contained an invalid value, like a name with unsupported characters.
+    #[error("InvalidArgument: {0}")]
+    InvalidArgument(String),
+
+    /// Memphis could not handle the request right now, e.g. because the cluster is not ready.
+    #[error("Unavailable: {0}")]
+    Unavailable(String),
+
+    #[error("{0}")]
+    Other(String),
+}
+
+impl MemphisServerError {
+    /// Whether sending the request again may succeed.
+    pub fn is_retryable(&self) -> bool {
+        matches!(self, MemphisServerError::Unavailable(_))
+    }
+
+    /// The message Memphis responded with.
+    pub fn message(&self) -> &str {
+        match self {
+            MemphisServerError::StationNotFound(message)
+            | MemphisServerError::SchemaNotFound(message)
+            | MemphisServerError::NotFound(message)
+            | MemphisServerError::AlreadyExists(message)
+            | MemphisServerError::Unauthorized(message)
+            | MemphisServerError::InvalidArgument(message)
+            | MemphisServerError::Unavailable(message)
+            | MemphisServerError::Other(message) => message,
+        }
+    }
+}
+
+type ServerErrorKind = fn(String) -> MemphisServerError;
+
+/// The fixed parts of the messages Memphis responds with, as `(prefix, suffix, kind)`.
+/// The part in between is variable, usually the name of a station, schema or other entity.
+/// The first matching entry wins.
+const SERVER_ERRORS: &[(&str, &str, ServerErrorKind)] = &[
+    (
+        "Station ",
+        " does not exist",
+        MemphisServerError::StationNotFound,
+    ),
+    (
+        "Schema ",
+        " does not exist",
+        MemphisServerError::SchemaNotFound,
+    ),
+    (
+        "Station ",
+        " has no schema",
+        MemphisServerError::SchemaNotFound,
+    ),
+    ("", " does not exist", MemphisServerError::NotFound),
+    ("", " already exists", MemphisServerError::AlreadyExists),
+    ("Unauthorized", "", MemphisServerError::Unauthorized),
+    ("Forbidden", "", MemphisServerError::Unauthorized),
+    ("Cluster is not ready", "", MemphisServerError::Unavailable),
+    ("Timeout", "", MemphisServerError::Unavailable),
+    ("Invalid ", "", MemphisServerError::InvalidArgument),
+    ("Station name ", "", MemphisServerError::InvalidArgument),
+    ("Schema name ", "", MemphisServerError::InvalidArgument),
+    ("Producer name ", "", MemphisServerError::InvalidArgument),
+    ("Consumer name ", "", MemphisServerError::InvalidArgument),
+    (
+        "Consumer group name ",
+        "",
+        MemphisServerError::InvalidArgument,
+    ),
+];
+
+impl MemphisServerError {
+    /// Classifies the `error` Memphis responded with, by the fixed parts of its messages.
+    /// Messages which do not match any of them are [Other](MemphisServerError::Other).
+    pub(crate) fn from_message(message: String) -> Self {
+        // Some versions of Memphis prefix their errors.
+        let text = message.strip_prefix("memphis: ").unwrap_or(&message);
+
+        let kind = SERVER_ERRORS.iter().find(|(prefix, suffix, _)| {
+            text.len() >= prefix.len() + suffix.len()
+                && text.starts_with(prefix)
+                && text.ends_with(suffix)
+        });
+        match kind {
+            Some((_, _, kind)) => kind(message),
+            None => MemphisServerError::Other(message),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MemphisServerError;
+
+    fn classify(message: &str) -> MemphisServerError {
+        MemphisServerError::from_message(message.to_string())
+    }
+
+    #[test]
+    fn server_errors_are_classified() {
+        assert_eq!(
+            classify("Station orders does not exist"),
+            MemphisServerError::StationNotFound("Station orders does not exist".to_string())
+        );
+        assert_eq!(
+            classify("Schema payments does not exist"),
+            MemphisServerError::SchemaNotFound("Schema payments does not exist".to_string())
+        );
+        assert_eq!(
+            classify("Consumer group does not exist"),
+            MemphisServerError::NotFound("Consumer group does not exist".to_string())
+        );
+        assert_eq!(
+            classify("Station orders already exists"),
+            MemphisServerError::AlreadyExists("Station orders already exists".to_string())
+        );
+        assert_eq!(
+            classify("Unauthorized"),
+            MemphisServerError::Unauthorized("Unauthorized".to_string())
+        );
+        assert_eq!(
+            classify("Producer name has to include only letters/numbers"),
+            MemphisServerError::InvalidArgument(
+                "Producer name has to include only letters/numbers".to_string()
+            )
+        );
+        assert_eq!(
+            classify("memphis: Station orders does not exist"),
+            MemphisServerError::StationNotFound(
+                "memphis: Station orders does not exist".to_string()
+            )
+        );
+        assert_eq!(
+            classify("Something went wrong"),
+            MemphisServerError::Other("Something went wrong".to_string())
+        );
+
+        // Only the fixed parts of the messages are matched, not words anywhere in the message.
+        assert_eq!(
+            classify("Invalid schema: missing field"),
+            MemphisServerError::InvalidArgument("Invalid schema: missing field".to_string())
+        );
+        assert_eq!(
+            classify("Failed to reach station orders, unauthorized peer"),
+            MemphisServerError::Other(
+                "Failed to reach station orders, unauthorized peer".to_string()
+            )
+        );
+        assert_eq!(
+            classify("Station orders does not exist").message(),
+            "Station orders does not exist"
+        );
+    }
+}
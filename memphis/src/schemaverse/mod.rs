@@ -2,3 +2,4 @@ pub use schema_type::*;
 
 pub mod schema;
 mod schema_type;
+pub(crate) mod schema_updates;
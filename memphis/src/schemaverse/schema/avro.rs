@@ -0,0 +1,394 @@
+use std::collections::HashMap;
+
+use bytes::Bytes;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::{Map, Value};
+use thiserror::Error;
+
+use crate::schemaverse::schema::{ErrorData, SchemaValidationError, SchemaValidator};
+
+/// Validates that messages are records matching an Avro schema.
+///
+/// Like the other Memphis SDKs, records are sent as JSON, not in the Avro binary encoding,
+/// so a message is valid if it is a JSON document that can be encoded with the schema.
+/// `bytes` and `fixed` values are strings, whose length in bytes is checked against the schema.
+/// Values of unions are wrapped in an object naming the branch, e.g. `{"string": "a"}`,
+/// unless they are `null`.
+/// Use [encode_json](AvroSchemaValidator::encode_json) and
+/// [decode_json](AvroSchemaValidator::decode_json) to convert records from and to messages.
+pub struct AvroSchemaValidator {
+    schema: AvroSchema,
+    named_types: HashMap<String, AvroSchema>,
+}
+
+#[derive(Clone, Debug)]
+enum AvroSchema {
+    Null,
+    Boolean,
+    Int,
+    Long,
+    Float,
+    Double,
+    Bytes,
+    String,
+    Record(Vec<AvroField>),
+    Enum(Vec<String>),
+    Array(Box<AvroSchema>),
+    Map(Box<AvroSchema>),
+    Union(Vec<AvroSchema>),
+    Fixed(usize),
+    /// A reference to a named type, resolved when validating so types can be recursive.
+    Reference(String),
+}
+
+impl AvroSchema {
+    /// The name of the type, as used for the branches of a union.
+    fn name(&self) -> &str {
+        match self {
+            AvroSchema::Null => "null",
+            AvroSchema::Boolean => "boolean",
+            AvroSchema::Int => "int",
+            AvroSchema::Long => "long",
+            AvroSchema::Float => "float",
+            AvroSchema::Double => "double",
+            AvroSchema::Bytes => "bytes",
+            AvroSchema::String => "string",
+            AvroSchema::Array(_) => "array",
+            AvroSchema::Map(_) => "map",
+            AvroSchema::Reference(name) => name,
+            // Named types are always referenced, unions can not be nested.
+            AvroSchema::Record(_)
+            | AvroSchema::Enum(_)
+            | AvroSchema::Fixed(_)
+            | AvroSchema::Union(_) => "",
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+struct AvroField {
+    name: String,
+    schema: AvroSchema,
+    has_default: bool,
+}
+
+impl AvroSchemaValidator {
+    /// Creates a validator from a parsed Avro schema.
+    pub fn new(schema: Value) -> Result<Self, AvroSchemaError> {
+        let mut parser = SchemaParser::default();
+        let schema = parser.parse(&schema, None)?;
+
+        // References are resolved in the enclosing namespace first, then in the null namespace.
+        for reference in &parser.references {
+            if parser.named_types.contains_key(reference) {
+                continue;
+            }
+            let name = reference.rsplit('.').next().unwrap_or(reference);
+            let Some(schema) = parser.named_types.get(name).cloned() else {
+                return Err(AvroSchemaError::SchemaError(format!(
+                    "Unknown type '{}'",
+                    reference
+                )));
+            };
+            parser.named_types.insert(reference.clone(), schema);
+        }
+
+        Ok(Self {
+            schema,
+            named_types: parser.named_types,
+        })
+    }
+
+    /// Serializes a record into a JSON message, failing if it does not match the schema.
+    pub fn encode_json<T: Serialize>(&self, record: &T) -> Result<Bytes, AvroSchemaError> {
+        let value = serde_json::to_value(record)?;
+        self.validate_value(&value)?;
+
+        Ok(serde_json::to_vec(&value)?.into())
+    }
+
+    /// Deserializes a JSON message into a record, failing if it does not match the schema.
+    pub fn decode_json<T: DeserializeOwned>(&self, message: &[u8]) -> Result<T, AvroSchemaError> {
+        let value = serde_json::from_slice(message)?;
+        self.validate_value(&value)?;
+
+        Ok(serde_json::from_value(value)?)
+    }
+
+    fn validate_value(&self, value: &Value) -> Result<(), AvroSchemaError> {
+        self.check(&self.schema, value, "$")
+            .map_err(AvroSchemaError::ValidationError)
+    }
+
+    fn check(&self, schema: &AvroSchema, value: &Value, path: &str) -> Result<(), String> {
+        let valid = match schema {
+            AvroSchema::Null => value.is_null(),
+            AvroSchema::Boolean => value.is_boolean(),
+            AvroSchema::Int => value.as_i64().is_some_and(|n| i32::try_from(n).is_ok()),
+            AvroSchema::Long => value.is_i64(),
+            AvroSchema::Float => value
+                .as_f64()
+                .is_some_and(|n| n.is_finite() && n.abs() <= f32::MAX as f64),
+            AvroSchema::Double => value.as_f64().is_some_and(f64::is_finite),
+            AvroSchema::Bytes | AvroSchema::String => value.is_string(),
+            AvroSchema::Fixed(size) => value.as_str().is_some_and(|s| s.len() == *size),
+            AvroSchema::Enum(symbols) => value
+                .as_str()
+                .is_some_and(|s| symbols.iter().any(|symbol| symbol == s)),
+            AvroSchema::Array(items) => {
+                let Some(values) = value.as_array() else {
+                    return Err(format!("{}: expected an array", path));
+                };
+                for (i, value) in values.iter().enumerate() {
+                    self.check(items, value, &format!("{}[{}]", path, i))?;
+                }
+                true
+            }
+            AvroSchema::Map(values) => {
+                let Some(entries) = value.as_object() else {
+                    return Err(format!("{}: expected a map", path));
+                };
+                for (key, value) in entries {
+                    self.check(values, value, &format!("{}.{}", path, key))?;
+                }
+                true
+            }
+            AvroSchema::Record(fields) => {
+                let Some(entries) = value.as_object() else {
+                    return Err(format!("{}: expected a record", path));
+                };
+                for field in fields {
+                    let path = format!("{}.{}", path, field.name);
+                    match entries.get(&field.name) {
+                        Some(value) => self.check(&field.schema, value, &path)?,
+                        None if field.has_default => {}
+                        None => return Err(format!("{}: missing field", path)),
+                    }
+                }
+                true
+            }
+            // Like in the JSON encoding of Avro, values of a union are wrapped in an object
+            // naming the branch, e.g. `{"string": "a"}`. Only `null` is not wrapped.
+            AvroSchema::Union(schemas) => match value {
+                Value::Null => schemas
+                    .iter()
+                    .any(|schema| matches!(schema, AvroSchema::Null)),
+                Value::Object(branch) if branch.len() == 1 => {
+                    let (name, value) = branch.iter().next().expect("one entry");
+                    match schemas.iter().find(|schema| schema.name() == name) {
+                        Some(schema) => return self.check(schema, value, path),
+                        None => false,
+                    }
+                }
+                _ => false,
+            },
+            AvroSchema::Reference(name) => match self.named_types.get(name) {
+                Some(schema) => return self.check(schema, value, path),
+                None => false,
+            },
+        };
+
+        if valid {
+            Ok(())
+        } else {
+            Err(format!("{}: {} does not match the schema", path, value))
+        }
+    }
+}
+
+impl SchemaValidator for AvroSchemaValidator {
+    fn validate(&self, message: &Bytes) -> Result<(), SchemaValidationError> {
+        let value = serde_json::from_slice(message).map_err(AvroSchemaError::from)?;
+
+        Ok(self.validate_value(&value)?)
+    }
+
+    fn from_bytes(bytes: &Bytes) -> Result<Self, SchemaValidationError> {
+        let schema = serde_json::from_slice(bytes).map_err(AvroSchemaError::from)?;
+
+        Ok(Self::new(schema)?)
+    }
+}
+
+#[derive(Default)]
+struct SchemaParser {
+    named_types: HashMap<String, AvroSchema>,
+    references: Vec<String>,
+}
+
+impl SchemaParser {
+    fn parse(
+        &mut self,
+        schema: &Value,
+        namespace: Option<&str>,
+    ) -> Result<AvroSchema, AvroSchemaError> {
+        match schema {
+            Value::String(name) => Ok(self.parse_name(name, namespace)),
+            Value::Array(schemas) => Ok(AvroSchema::Union(
+                schemas
+                    .iter()
+                    .map(|schema| self.parse(schema, namespace))
+                    .collect::<Result<_, _>>()?,
+            )),
+            Value::Object(object) => self.parse_complex(object, namespace),
+            other => Err(AvroSchemaError::SchemaError(format!(
+                "Invalid schema: {}",
+                other
+            ))),
+        }
+    }
+
+    fn parse_name(&mut self, name: &str, namespace: Option<&str>) -> AvroSchema {
+        match name {
+            "null" => AvroSchema::Null,
+            "boolean" => AvroSchema::Boolean,
+            "int" => AvroSchema::Int,
+            "long" => AvroSchema::Long,
+            "float" => AvroSchema::Float,
+            "double" => AvroSchema::Double,
+            "bytes" => AvroSchema::Bytes,
+            "string" => AvroSchema::String,
+            name => {
+                let full_name = full_name(name, namespace);
+                self.references.push(full_name.clone());
+                AvroSchema::Reference(full_name)
+            }
+        }
+    }
+
+    fn parse_complex(
+        &mut self,
+        object: &Map<String, Value>,
+        namespace: Option<&str>,
+    ) -> Result<AvroSchema, AvroSchemaError> {
+        let schema_type = match object.get("type") {
+            Some(Value::String(schema_type)) => schema_type.as_str(),
+            Some(schema) => return self.parse(schema, namespace),
+            None => {
+                return Err(AvroSchemaError::SchemaError(
+                    "Missing 'type' in schema".to_string(),
+                ))
+            }
+        };
+
+        let schema = match schema_type {
+            "array" => {
+                AvroSchema::Array(Box::new(self.parse(required(object, "items")?, namespace)?))
+            }
+            "map" => AvroSchema::Map(Box::new(
+                self.parse(required(object, "values")?, namespace)?,
+            )),
+            "record" | "error" | "enum" | "fixed" => {
+                let name = required_str(object, "name")?;
+                let namespace = object
+                    .get("namespace")
+                    .and_then(Value::as_str)
+                    .or(namespace);
+                let full_name = full_name(name, namespace);
+                if self.named_types.contains_key(&full_name) {
+                    return Err(AvroSchemaError::SchemaError(format!(
+                        "Type '{}' is defined more than once",
+                        full_name
+                    )));
+                }
+                // The namespace of nested types defaults to the one of the enclosing type.
+                let namespace = full_name.rsplit_once('.').map(|(namespace, _)| namespace);
+
+                let schema = match schema_type {
+                    "enum" => AvroSchema::Enum(
+                        required_array(object, "symbols")?
+                            .iter()
+                            .map(|symbol| match symbol {
+                                Value::String(symbol) => Ok(symbol.clone()),
+                                other => Err(AvroSchemaError::SchemaError(format!(
+                                    "Invalid enum symbol: {}",
+                                    other
+                                ))),
+                            })
+                            .collect::<Result<_, _>>()?,
+                    ),
+                    "fixed" => match required(object, "size")?.as_u64() {
+                        Some(size) => AvroSchema::Fixed(size as usize),
+                        None => {
+                            return Err(AvroSchemaError::SchemaError(
+                                "Invalid 'size' in fixed type".to_string(),
+                            ))
+                        }
+                    },
+                    _ => {
+                        // Registered before parsing the fields, so the record can reference itself.
+                        self.named_types
+                            .insert(full_name.clone(), AvroSchema::Record(Vec::new()));
+
+                        let mut fields = Vec::new();
+                        for field in required_array(object, "fields")? {
+                            let Some(field) = field.as_object() else {
+                                return Err(AvroSchemaError::SchemaError(format!(
+                                    "Invalid field: {}",
+                                    field
+                                )));
+                            };
+                            fields.push(AvroField {
+                                name: required_str(field, "name")?.to_string(),
+                                schema: self.parse(required(field, "type")?, namespace)?,
+                                has_default: field.contains_key("default"),
+                            });
+                        }
+                        AvroSchema::Record(fields)
+                    }
+                };
+
+                self.named_types.insert(full_name.clone(), schema);
+                AvroSchema::Reference(full_name)
+            }
+            // Primitive types, possibly with a logical type, or a reference to a named type.
+            name => self.parse_name(name, namespace),
+        };
+        Ok(schema)
+    }
+}
+
+fn full_name(name: &str, namespace: Option<&str>) -> String {
+    match namespace {
+        Some(namespace) if !name.contains('.') && !namespace.is_empty() => {
+            format!("{}.{}", namespace, name)
+        }
+        _ => name.to_string(),
+    }
+}
+
+fn required<'a>(object: &'a Map<String, Value>, key: &str) -> Result<&'a Value, AvroSchemaError> {
+    object
+        .get(key)
+        .ok_or_else(|| AvroSchemaError::SchemaError(format!("Missing '{}' in schema", key)))
+}
+
+fn required_str<'a>(object: &'a Map<String, Value>, key: &str) -> Result<&'a str, AvroSchemaError> {
+    required(object, key)?
+        .as_str()
+        .ok_or_else(|| AvroSchemaError::SchemaError(format!("'{}' must be a string", key)))
+}
+
+fn required_array<'a>(
+    object: &'a Map<String, Value>,
+    key: &str,
+) -> Result<&'a Vec<Value>, AvroSchemaError> {
+    required(object, key)?
+        .as_array()
+        .ok_or_else(|| AvroSchemaError::SchemaError(format!("'{}' must be an array", key)))
+}
+
+#[derive(Debug, Error)]
+pub enum AvroSchemaError {
+    #[error("Serde Error: {0}")]
+    SerdeError(#[from] serde_json::Error),
+
+    #[error("Error while parsing schema: {0}")]
+    SchemaError(String),
+
+    #[error("Error while validating message: {0}")]
+    ValidationError(String),
+}
+
+impl ErrorData for AvroSchemaError {}
@@ -0,0 +1,356 @@
+use std::collections::{HashMap, HashSet};
+
+use bytes::Bytes;
+use graphql_parser::query::{
+    Definition, Document, FragmentDefinition, OperationDefinition, Selection, SelectionSet,
+    TypeCondition,
+};
+use graphql_parser::schema::{self, Type, TypeDefinition, TypeExtension};
+use thiserror::Error;
+
+use crate::schemaverse::schema::{ErrorData, SchemaValidationError, SchemaValidator};
+
+const BUILT_IN_SCALARS: [&str; 5] = ["Int", "Float", "String", "Boolean", "ID"];
+
+/// Validates that messages are GraphQL documents that are valid against an SDL schema.
+///
+/// Every field that is selected has to exist on its type, only declared arguments may be used
+/// and fragments have to reference known fragments and types.
+pub struct GraphQlSchemaValidator {
+    types: HashMap<String, GraphQlType>,
+    query: Option<String>,
+    mutation: Option<String>,
+    subscription: Option<String>,
+}
+
+enum GraphQlType {
+    /// Scalars and enums, which can not have a selection set.
+    Leaf,
+    /// Objects, interfaces and unions, which need a selection set.
+    Composite(HashMap<String, GraphQlField>),
+    Input,
+}
+
+struct GraphQlField {
+    arguments: HashSet<String>,
+    type_name: String,
+}
+
+impl GraphQlSchemaValidator {
+    /// Creates a validator from a schema in the GraphQL schema definition language.
+    pub fn new(sdl: &str) -> Result<Self, GraphQlSchemaError> {
+        let document = graphql_parser::parse_schema::<String>(sdl)?;
+
+        let mut types: HashMap<String, GraphQlType> = BUILT_IN_SCALARS
+            .iter()
+            .map(|name| (name.to_string(), GraphQlType::Leaf))
+            .collect();
+        let mut schema_definition = None;
+        let mut extensions = Vec::new();
+
+        for definition in document.definitions {
+            match definition {
+                schema::Definition::SchemaDefinition(definition) => {
+                    schema_definition = Some(definition)
+                }
+                schema::Definition::TypeDefinition(definition) => {
+                    let (name, graphql_type) = match definition {
+                        TypeDefinition::Scalar(t) => (t.name, GraphQlType::Leaf),
+                        TypeDefinition::Enum(t) => (t.name, GraphQlType::Leaf),
+                        TypeDefinition::InputObject(t) => (t.name, GraphQlType::Input),
+                        TypeDefinition::Object(t) => (t.name, composite_type(t.fields)),
+                        TypeDefinition::Interface(t) => (t.name, composite_type(t.fields)),
+                        TypeDefinition::Union(t) => (t.name, composite_type(Vec::new())),
+                    };
+                    if types.insert(name.clone(), graphql_type).is_some()
+                        && !BUILT_IN_SCALARS.contains(&name.as_str())
+                    {
+                        return Err(GraphQlSchemaError::InvalidSchema(format!(
+                            "Type '{}' is defined more than once",
+                            name
+                        )));
+                    }
+                }
+                schema::Definition::TypeExtension(extension) => extensions.push(extension),
+                schema::Definition::DirectiveDefinition(_) => {}
+            }
+        }
+
+        for extension in extensions {
+            let (name, new_fields) = match extension {
+                TypeExtension::Object(t) => (t.name, t.fields),
+                TypeExtension::Interface(t) => (t.name, t.fields),
+                _ => continue,
+            };
+            match types.get_mut(&name) {
+                Some(GraphQlType::Composite(fields)) => {
+                    if let GraphQlType::Composite(new_fields) = composite_type(new_fields) {
+                        fields.extend(new_fields);
+                    }
+                }
+                _ => {
+                    return Err(GraphQlSchemaError::InvalidSchema(format!(
+                        "Cannot extend unknown type '{}'",
+                        name
+                    )))
+                }
+            }
+        }
+
+        let (query, mutation, subscription) = match schema_definition {
+            Some(definition) => (
+                definition.query,
+                definition.mutation,
+                definition.subscription,
+            ),
+            None => {
+                let default_root = |name: &str| types.contains_key(name).then(|| name.to_string());
+                (
+                    default_root("Query"),
+                    default_root("Mutation"),
+                    default_root("Subscription"),
+                )
+            }
+        };
+
+        let validator = Self {
+            types,
+            query,
+            mutation,
+            subscription,
+        };
+        validator.check_types()?;
+        Ok(validator)
+    }
+
+    /// Ensures that every type referenced by the schema is defined.
+    fn check_types(&self) -> Result<(), GraphQlSchemaError> {
+        let roots = [&self.query, &self.mutation, &self.subscription];
+        let referenced = roots.into_iter().flatten().chain(
+            self.types
+                .values()
+                .filter_map(|t| match t {
+                    GraphQlType::Composite(fields) => Some(fields.values()),
+                    _ => None,
+                })
+                .flatten()
+                .map(|field| &field.type_name),
+        );
+
+        for name in referenced {
+            if !self.types.contains_key(name) {
+                return Err(GraphQlSchemaError::InvalidSchema(format!(
+                    "Unknown type '{}'",
+                    name
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    fn validate_document(&self, document: &Document<String>) -> Result<(), GraphQlSchemaError> {
+        let fragments: HashMap<&str, &FragmentDefinition<String>> = document
+            .definitions
+            .iter()
+            .filter_map(|definition| match definition {
+                Definition::Fragment(fragment) => Some((fragment.name.as_str(), fragment)),
+                _ => None,
+            })
+            .collect();
+
+        let mut has_operation = false;
+        for definition in &document.definitions {
+            let Definition::Operation(operation) = definition else {
+                continue;
+            };
+            has_operation = true;
+
+            let (root, operation_type, selection_set) = match operation {
+                OperationDefinition::SelectionSet(s) => (&self.query, "query", s),
+                OperationDefinition::Query(q) => (&self.query, "query", &q.selection_set),
+                OperationDefinition::Mutation(m) => (&self.mutation, "mutation", &m.selection_set),
+                OperationDefinition::Subscription(s) => {
+                    (&self.subscription, "subscription", &s.selection_set)
+                }
+            };
+            let Some(root) = root else {
+                return Err(GraphQlSchemaError::ValidationError(format!(
+                    "Schema does not support {} operations",
+                    operation_type
+                )));
+            };
+
+            self.validate_selection_set(root, selection_set, &fragments, &mut Vec::new())?;
+        }
+
+        if !has_operation {
+            return Err(GraphQlSchemaError::ValidationError(
+                "Document does not contain any operation".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    fn validate_selection_set<'a>(
+        &self,
+        type_name: &str,
+        selection_set: &'a SelectionSet<String>,
+        fragments: &HashMap<&str, &'a FragmentDefinition<String>>,
+        visited_fragments: &mut Vec<&'a str>,
+    ) -> Result<(), GraphQlSchemaError> {
+        let Some(GraphQlType::Composite(fields)) = self.types.get(type_name) else {
+            return Err(GraphQlSchemaError::ValidationError(format!(
+                "Type '{}' can not have a selection set",
+                type_name
+            )));
+        };
+
+        for selection in &selection_set.items {
+            match selection {
+                Selection::Field(field) => {
+                    if field.name == "__typename" {
+                        continue;
+                    }
+                    let Some(definition) = fields.get(&field.name) else {
+                        return Err(GraphQlSchemaError::ValidationError(format!(
+                            "Cannot query field '{}' on type '{}'",
+                            field.name, type_name
+                        )));
+                    };
+                    if let Some((argument, _)) = field
+                        .arguments
+                        .iter()
+                        .find(|(argument, _)| !definition.arguments.contains(argument))
+                    {
+                        return Err(GraphQlSchemaError::ValidationError(format!(
+                            "Unknown argument '{}' on field '{}.{}'",
+                            argument, type_name, field.name
+                        )));
+                    }
+
+                    let is_composite = matches!(
+                        self.types.get(&definition.type_name),
+                        Some(GraphQlType::Composite(_))
+                    );
+                    match (is_composite, field.selection_set.items.is_empty()) {
+                        (true, true) => {
+                            return Err(GraphQlSchemaError::ValidationError(format!(
+                                "Field '{}.{}' of type '{}' must have a selection of subfields",
+                                type_name, field.name, definition.type_name
+                            )))
+                        }
+                        (true, false) => self.validate_selection_set(
+                            &definition.type_name,
+                            &field.selection_set,
+                            fragments,
+                            visited_fragments,
+                        )?,
+                        (false, true) => {}
+                        (false, false) => {
+                            return Err(GraphQlSchemaError::ValidationError(format!(
+                                "Field '{}.{}' of type '{}' can not have a selection of subfields",
+                                type_name, field.name, definition.type_name
+                            )))
+                        }
+                    }
+                }
+                Selection::InlineFragment(fragment) => {
+                    let type_name = match &fragment.type_condition {
+                        Some(TypeCondition::On(name)) => name.as_str(),
+                        None => type_name,
+                    };
+                    self.validate_selection_set(
+                        type_name,
+                        &fragment.selection_set,
+                        fragments,
+                        visited_fragments,
+                    )?;
+                }
+                Selection::FragmentSpread(spread) => {
+                    let name = spread.fragment_name.as_str();
+                    let Some(fragment) = fragments.get(name) else {
+                        return Err(GraphQlSchemaError::ValidationError(format!(
+                            "Unknown fragment '{}'",
+                            name
+                        )));
+                    };
+                    if visited_fragments.contains(&fragment.name.as_str()) {
+                        return Err(GraphQlSchemaError::ValidationError(format!(
+                            "Fragment '{}' spreads itself",
+                            name
+                        )));
+                    }
+
+                    let TypeCondition::On(fragment_type) = &fragment.type_condition;
+                    visited_fragments.push(fragment.name.as_str());
+                    self.validate_selection_set(
+                        fragment_type,
+                        &fragment.selection_set,
+                        fragments,
+                        visited_fragments,
+                    )?;
+                    visited_fragments.pop();
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn composite_type(fields: Vec<schema::Field<String>>) -> GraphQlType {
+    GraphQlType::Composite(
+        fields
+            .into_iter()
+            .map(|field| {
+                let definition = GraphQlField {
+                    arguments: field.arguments.into_iter().map(|a| a.name).collect(),
+                    type_name: named_type(field.field_type),
+                };
+                (field.name, definition)
+            })
+            .collect(),
+    )
+}
+
+fn named_type(field_type: Type<String>) -> String {
+    match field_type {
+        Type::NamedType(name) => name,
+        Type::ListType(inner) | Type::NonNullType(inner) => named_type(*inner),
+    }
+}
+
+impl SchemaValidator for GraphQlSchemaValidator {
+    fn validate(&self, message: &Bytes) -> Result<(), SchemaValidationError> {
+        let query = std::str::from_utf8(message).map_err(GraphQlSchemaError::from)?;
+        let document =
+            graphql_parser::parse_query::<String>(query).map_err(GraphQlSchemaError::from)?;
+
+        Ok(self.validate_document(&document)?)
+    }
+
+    fn from_bytes(bytes: &Bytes) -> Result<Self, SchemaValidationError> {
+        let sdl = std::str::from_utf8(bytes).map_err(GraphQlSchemaError::from)?;
+
+        Ok(Self::new(sdl)?)
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum GraphQlSchemaError {
+    #[error("Invalid UTF-8: {0}")]
+    Utf8Error(#[from] std::str::Utf8Error),
+
+    #[error("Error while parsing schema: {0}")]
+    SchemaParseError(#[from] graphql_parser::schema::ParseError),
+
+    #[error("Invalid schema: {0}")]
+    InvalidSchema(String),
+
+    #[error("Error while parsing message: {0}")]
+    QueryParseError(#[from] graphql_parser::query::ParseError),
+
+    #[error("Error while validating message: {0}")]
+    ValidationError(String),
+}
+
+impl ErrorData for GraphQlSchemaError {}
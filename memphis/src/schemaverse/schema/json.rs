@@ -11,6 +11,7 @@ pub struct JsonSchemaValidator {
 }
 
 impl JsonSchemaValidator {
+    #[allow(clippy::result_large_err)]
     pub fn new(value: serde_json::Value) -> Result<Self, JsonSchemaError> {
         let schema = JSONSchema::options()
             .with_draft(Draft::Draft7)
@@ -1,9 +1,12 @@
 use std::fmt::Debug;
 use std::path::Path;
+use std::sync::Arc;
 
 use bytes::Bytes;
 use thiserror::Error;
 
+use crate::models::response::SchemaUpdate;
+use crate::schemaverse::SchemaType;
 use crate::RequestError;
 
 #[async_trait::async_trait]
@@ -37,6 +40,25 @@ pub enum SchemaValidationError {
 
     #[error("Schema invalid: {0:?}")]
     SchemaInvalid(Box<dyn ErrorData>),
+
+    /// The schema type is unknown, or the feature of its validator is not enabled.
+    #[error("Schema type '{0}' is not supported")]
+    UnsupportedSchemaType(String),
+
+    #[error("Memphis did not send the schema of the station in time")]
+    SchemaNotReceived,
+}
+
+impl SchemaValidationError {
+    /// Whether the operation may succeed if it is tried again.
+    /// Invalid schemas and messages failing the validation are not retryable.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            SchemaValidationError::RequestError(e) => e.is_retryable(),
+            SchemaValidationError::SchemaNotReceived => true,
+            _ => false,
+        }
+    }
 }
 
 impl<E: ErrorData + 'static> From<E> for SchemaValidationError {
@@ -45,5 +67,53 @@ impl<E: ErrorData + 'static> From<E> for SchemaValidationError {
     }
 }
 
+/// Creates the validator for a schema sent by Memphis.
+pub(crate) fn create_validator(
+    schema: &SchemaUpdate,
+) -> Result<Arc<dyn SchemaValidator>, SchemaValidationError> {
+    let schema_type = schema.type_name.parse::<SchemaType>()?;
+
+    match schema_type {
+        #[cfg(feature = "validator_json")]
+        SchemaType::Json => {
+            let content = Bytes::from(schema.active_version.schema_content.clone());
+            Ok(Arc::new(json::JsonSchemaValidator::from_bytes(&content)?))
+        }
+        #[cfg(feature = "validator_avro")]
+        SchemaType::Avro => {
+            let content = Bytes::from(schema.active_version.schema_content.clone());
+            Ok(Arc::new(avro::AvroSchemaValidator::from_bytes(&content)?))
+        }
+        #[cfg(feature = "validator_graphql")]
+        SchemaType::GraphQL => {
+            let content = Bytes::from(schema.active_version.schema_content.clone());
+            Ok(Arc::new(graphql::GraphQlSchemaValidator::from_bytes(
+                &content,
+            )?))
+        }
+        #[cfg(feature = "validator_protobuf")]
+        SchemaType::Protobuf => {
+            // Memphis compiles the schema and sends the resulting descriptor set.
+            let version = &schema.active_version;
+            Ok(Arc::new(
+                protobuf::ProtobufSchemaValidator::from_descriptor_set(
+                    version.descriptor.as_bytes(),
+                    Some(version.message_struct_name.as_str()),
+                )?,
+            ))
+        }
+        #[allow(unreachable_patterns)]
+        _ => Err(SchemaValidationError::UnsupportedSchemaType(
+            schema_type.to_string(),
+        )),
+    }
+}
+
+#[cfg(feature = "validator_avro")]
+pub mod avro;
+#[cfg(feature = "validator_graphql")]
+pub mod graphql;
 #[cfg(feature = "validator_json")]
 pub mod json;
+#[cfg(feature = "validator_protobuf")]
+pub mod protobuf;
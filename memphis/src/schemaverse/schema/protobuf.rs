@@ -0,0 +1,90 @@
+use bytes::Bytes;
+use prost_reflect::{DescriptorError, DescriptorPool, DynamicMessage, MessageDescriptor};
+use thiserror::Error;
+
+use crate::schemaverse::schema::{ErrorData, SchemaValidationError, SchemaValidator};
+
+/// Validates that messages can be decoded as a specific protobuf message.
+pub struct ProtobufSchemaValidator {
+    message: MessageDescriptor,
+}
+
+impl ProtobufSchemaValidator {
+    pub fn new(message: MessageDescriptor) -> Self {
+        Self { message }
+    }
+
+    /// Creates a validator from a serialized `FileDescriptorSet`, as sent by Memphis.
+    ///
+    /// # Arguments
+    /// * `descriptor` - The serialized `FileDescriptorSet`.
+    /// * `message_name` - The name of the message the payloads are validated against.
+    ///   If `None`, the first message of the set is used.
+    pub fn from_descriptor_set(
+        descriptor: &[u8],
+        message_name: Option<&str>,
+    ) -> Result<Self, ProtobufSchemaError> {
+        if descriptor.is_empty() {
+            return Err(ProtobufSchemaError::MissingDescriptor);
+        }
+        let pool = DescriptorPool::decode(descriptor)?;
+
+        Self::from_pool(&pool, message_name)
+    }
+
+    fn from_pool(
+        pool: &DescriptorPool,
+        message_name: Option<&str>,
+    ) -> Result<Self, ProtobufSchemaError> {
+        let message = match message_name.filter(|name| !name.is_empty()) {
+            None => pool.all_messages().next(),
+            Some(name) => pool
+                .get_message_by_name(name)
+                .or_else(|| pool.all_messages().find(|m| m.name() == name)),
+        };
+
+        match message {
+            Some(message) => Ok(Self::new(message)),
+            None => Err(ProtobufSchemaError::MessageNotFound(
+                message_name.unwrap_or_default().to_string(),
+            )),
+        }
+    }
+
+    /// Returns the descriptor of the message the payloads are validated against.
+    pub fn message_descriptor(&self) -> &MessageDescriptor {
+        &self.message
+    }
+}
+
+impl SchemaValidator for ProtobufSchemaValidator {
+    fn validate(&self, message: &Bytes) -> Result<(), SchemaValidationError> {
+        DynamicMessage::decode(self.message.clone(), message.as_ref())
+            .map_err(ProtobufSchemaError::from)?;
+
+        Ok(())
+    }
+
+    /// Creates a validator for the first message of a serialized `FileDescriptorSet`,
+    /// e.g. as produced by `protoc --descriptor_set_out`.
+    fn from_bytes(bytes: &Bytes) -> Result<Self, SchemaValidationError> {
+        Ok(Self::from_descriptor_set(bytes, None)?)
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum ProtobufSchemaError {
+    #[error("The schema has no descriptor")]
+    MissingDescriptor,
+
+    #[error("Invalid descriptor: {0}")]
+    DescriptorError(#[from] DescriptorError),
+
+    #[error("Message '{0}' not found in schema")]
+    MessageNotFound(String),
+
+    #[error("Error while validating message: {0}")]
+    ValidationError(#[from] prost_reflect::prost::DecodeError),
+}
+
+impl ErrorData for ProtobufSchemaError {}
@@ -1,3 +1,7 @@
+use std::str::FromStr;
+
+use crate::schemaverse::schema::SchemaValidationError;
+
 #[derive(Debug)]
 pub enum SchemaType {
     #[cfg(feature = "validator_json")]
@@ -6,17 +10,51 @@ pub enum SchemaType {
     GraphQL,
     #[cfg(feature = "validator_protobuf")]
     Protobuf,
+    #[cfg(feature = "validator_avro")]
+    Avro,
 }
 
+impl SchemaType {
+    /// Returns the name Memphis uses for this schema type.
+    pub fn as_str(&self) -> &'static str {
+        match *self {
+            #[cfg(feature = "validator_json")]
+            SchemaType::Json => "json",
+            #[cfg(feature = "validator_graphql")]
+            SchemaType::GraphQL => "graphql",
+            #[cfg(feature = "validator_protobuf")]
+            SchemaType::Protobuf => "protobuf",
+            #[cfg(feature = "validator_avro")]
+            SchemaType::Avro => "avro",
+        }
+    }
+}
+
+#[allow(clippy::to_string_trait_impl)]
 impl ToString for SchemaType {
     fn to_string(&self) -> String {
-        match self {
+        self.as_str().to_string()
+    }
+}
+
+impl FromStr for SchemaType {
+    type Err = SchemaValidationError;
+
+    /// Parses the schema type name used by Memphis.
+    /// Fails if the type is unknown or the feature of its validator is not enabled.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
             #[cfg(feature = "validator_json")]
-            SchemaType::Json => "json".to_string(),
+            "json" => Ok(SchemaType::Json),
             #[cfg(feature = "validator_graphql")]
-            SchemaType::GraphQL => "graphql".to_string(),
+            "graphql" => Ok(SchemaType::GraphQL),
             #[cfg(feature = "validator_protobuf")]
-            SchemaType::Protobuf => "protobuf".to_string(),
+            "protobuf" => Ok(SchemaType::Protobuf),
+            #[cfg(feature = "validator_avro")]
+            "avro" => Ok(SchemaType::Avro),
+            other => Err(SchemaValidationError::UnsupportedSchemaType(
+                other.to_string(),
+            )),
         }
     }
 }
@@ -0,0 +1,192 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Weak};
+
+use async_nats::{Client, Subscriber};
+use futures_util::StreamExt;
+use log::{debug, error, info, warn};
+use tokio::sync::{Mutex, RwLock};
+use tokio_util::sync::{CancellationToken, DropGuard};
+
+use crate::constants::memphis_constants::MemphisSubscriptions;
+use crate::models::response::{
+    SchemaUpdate, SchemaUpdateMessage, SCHEMA_UPDATE_TYPE_DROP, SCHEMA_UPDATE_TYPE_INIT,
+};
+use crate::schemaverse::schema::{create_validator, SchemaValidationError, SchemaValidator};
+use crate::RequestError;
+
+/// The validator of a station, shared by every producer of the station.
+pub(crate) type SchemaSlot = Arc<RwLock<Option<Arc<dyn SchemaValidator>>>>;
+
+/// Keeps track of the schemas of all stations used by a [MemphisClient](crate::memphis_client::MemphisClient).
+///
+/// While a station has at least one live producer, the registry listens on
+/// `$memphis_schema_updates_<station>` and swaps the validator of the station whenever
+/// a new schema version is activated or the schema is detached.
+///
+/// The registry only holds weak references, so the subscription stops once the last producer
+/// of the station is dropped, and the entry is pruned once no station uses the schema anymore.
+#[derive(Clone, Default)]
+pub(crate) struct SchemaUpdatesRegistry {
+    stations: Arc<Mutex<HashMap<String, StationSchema>>>,
+}
+
+#[derive(Default)]
+struct StationSchema {
+    schema: Weak<RwLock<Option<Arc<dyn SchemaValidator>>>>,
+    subscription: Weak<SchemaSubscription>,
+}
+
+impl StationSchema {
+    fn is_unused(&self) -> bool {
+        self.schema.strong_count() == 0 && self.subscription.strong_count() == 0
+    }
+
+    /// Returns the validator slot, creating an empty one if no station uses it anymore.
+    fn schema(&mut self) -> SchemaSlot {
+        self.schema.upgrade().unwrap_or_else(|| {
+            let schema = SchemaSlot::default();
+            self.schema = Arc::downgrade(&schema);
+            schema
+        })
+    }
+}
+
+/// Listens to the schema updates of a station until every producer holding it was dropped.
+pub(crate) struct SchemaSubscription {
+    _cancellation: DropGuard,
+}
+
+impl SchemaUpdatesRegistry {
+    /// Returns the validator slot of the station.
+    pub(crate) async fn get_schema(&self, internal_station_name: &str) -> SchemaSlot {
+        let mut stations = self.stations.lock().await;
+        stations.retain(|_, station| !station.is_unused());
+        stations
+            .entry(internal_station_name.to_string())
+            .or_default()
+            .schema()
+    }
+
+    /// Starts listening for schema updates of the station, unless a producer of the station
+    /// already does. The producer keeps the returned subscription for as long as it lives.
+    pub(crate) async fn add_producer(
+        &self,
+        broker_connection: &Client,
+        internal_station_name: &str,
+    ) -> Result<Arc<SchemaSubscription>, RequestError> {
+        let mut stations = self.stations.lock().await;
+        stations.retain(|_, station| !station.is_unused());
+        let station = stations
+            .entry(internal_station_name.to_string())
+            .or_default();
+
+        if let Some(subscription) = station.subscription.upgrade() {
+            return Ok(subscription);
+        }
+
+        let subject = format!(
+            "{}{}",
+            MemphisSubscriptions::SchemaUpdatesPrefix,
+            internal_station_name
+        );
+        let subscriber = broker_connection
+            .subscribe(subject)
+            .await
+            .map_err(|e| RequestError::NatsError(e.into()))?;
+
+        let cancellation_token = CancellationToken::new();
+        tokio::spawn(listen_to_schema_updates(
+            internal_station_name.to_string(),
+            subscriber,
+            station.schema(),
+            cancellation_token.clone(),
+        ));
+
+        let subscription = Arc::new(SchemaSubscription {
+            _cancellation: cancellation_token.drop_guard(),
+        });
+        station.subscription = Arc::downgrade(&subscription);
+        Ok(subscription)
+    }
+}
+
+/// Replaces the validator in the slot with one for the given schema.
+/// An empty schema name means that no schema is attached to the station.
+///
+/// If the validator can not be created, the slot is cleared, so messages are not validated
+/// against a version of the schema which is no longer active.
+pub(crate) async fn install_schema(
+    schema_slot: &SchemaSlot,
+    schema: &SchemaUpdate,
+) -> Result<(), SchemaValidationError> {
+    let validator = if schema.schema_name.is_empty() {
+        None
+    } else {
+        match create_validator(schema) {
+            Ok(validator) => Some(validator),
+            Err(e) => {
+                *schema_slot.write().await = None;
+                return Err(e);
+            }
+        }
+    };
+
+    *schema_slot.write().await = validator;
+    Ok(())
+}
+
+async fn listen_to_schema_updates(
+    internal_station_name: String,
+    mut subscriber: Subscriber,
+    schema: SchemaSlot,
+    cancellation_token: CancellationToken,
+) {
+    debug!(
+        "Started listening to schema updates of station '{}'",
+        &internal_station_name
+    );
+    loop {
+        tokio::select! {
+            Some(message) = subscriber.next() => {
+                let update = match serde_json::from_slice::<SchemaUpdateMessage>(&message.payload) {
+                    Ok(update) => update,
+                    Err(e) => {
+                        error!("Error while parsing schema update. {}", e);
+                        continue;
+                    }
+                };
+
+                match (update.update_type, update.init) {
+                    (SCHEMA_UPDATE_TYPE_INIT, Some(init)) => match install_schema(&schema, &init).await {
+                        Ok(()) if init.schema_name.is_empty() => {
+                            info!("Schema of station '{}' was detached", &internal_station_name)
+                        }
+                        Ok(()) => info!(
+                            "Schema of station '{}' was updated to '{}' version {}",
+                            &internal_station_name,
+                            &init.schema_name,
+                            init.active_version.version_number
+                        ),
+                        Err(e) => error!(
+                            "Error creating validator for schema '{}'. Producing to station '{}' without validation. {}",
+                            &init.schema_name, &internal_station_name, e
+                        ),
+                    },
+                    (SCHEMA_UPDATE_TYPE_DROP, _) => {
+                        *schema.write().await = None;
+                        info!("Schema of station '{}' was detached", &internal_station_name);
+                    }
+                    (update_type, _) => {
+                        warn!("Received unknown schema update type {}", update_type);
+                    }
+                }
+            },
+            _ = cancellation_token.cancelled() => break,
+            else => break
+        }
+    }
+    debug!(
+        "Stopped listening to schema updates of station '{}'",
+        &internal_station_name
+    );
+}
@@ -1,4 +1,3 @@
-use std::collections::HashSet;
 use std::sync::Arc;
 
 use crate::constants::memphis_constants::MemphisSpecialStation;
@@ -6,23 +5,18 @@ use crate::helper::memphis_util::get_internal_name;
 use crate::memphis_client::MemphisClient;
 use crate::models::request::{CreateStationRequest, DestroyStationRequest, DlsConfiguration};
 #[cfg(feature = "schemaverse")]
-use crate::schemaverse::schema::SchemaValidator;
+use crate::schemaverse::schema_updates::SchemaSlot;
 use crate::station::memphis_station_options::MemphisStationsOptions;
 use crate::RequestError;
 use log::{error, info};
-use tokio::sync::RwLock;
-
-//static SEED: u32 = 31;
 
 #[derive(Clone)]
 pub struct MemphisStation {
     pub(crate) memphis_client: MemphisClient,
     pub(crate) options: Arc<MemphisStationsOptions>,
 
-    pub(crate) known_messages: Arc<RwLock<HashSet<String>>>,
-
     #[cfg(feature = "schemaverse")]
-    pub(crate) schema: Option<Arc<dyn SchemaValidator>>,
+    pub(crate) schema: SchemaSlot,
 }
 
 impl MemphisStation {
@@ -57,12 +51,17 @@ impl MemphisStation {
 
         info!("Created station {}", &options.station_name);
 
+        #[cfg(feature = "schemaverse")]
+        let schema = client
+            .schema_updates
+            .get_schema(&get_internal_name(&options.station_name))
+            .await;
+
         Ok(Self {
             memphis_client: client,
             options: Arc::new(options),
-            known_messages: Arc::new(RwLock::new(HashSet::new())),
             #[cfg(feature = "schemaverse")]
-            schema: None,
+            schema,
         })
     }
 
@@ -103,11 +102,6 @@ impl MemphisStation {
     pub fn get_internal_subject_name(&self, partition: Option<u32>) -> String {
         format!("{}.final", self.get_internal_name(partition))
     }
-
-    //TODO: Implement this once the other parts are implemented.
-    // pub(crate) fn get_partition_key(&self, key: &str, partition_count: u32) -> u32 {
-    //     murmur3_32(&mut Cursor::new(key), SEED).unwrap_or(0) % partition_count
-    // }
 }
 
 #[cfg(feature = "consumers")]
@@ -148,6 +142,136 @@ mod consumers {
     }
 }
 
+#[cfg(feature = "schemaverse")]
+mod schemaverse {
+    use futures_util::StreamExt;
+    use log::{error, info};
+
+    use crate::constants::memphis_constants::{MemphisSpecialStation, MemphisSubscriptions};
+    use crate::models::request::{AttachSchemaRequest, DetachSchemaRequest};
+    use crate::models::response::{SchemaUpdate, SchemaUpdateMessage, SCHEMA_UPDATE_TYPE_INIT};
+    use crate::schemaverse::schema::SchemaValidationError;
+    use crate::schemaverse::schema_updates;
+    use crate::station::MemphisStation;
+    use crate::RequestError;
+
+    impl MemphisStation {
+        /// Attaches the schema with the given name to the station.
+        ///
+        /// Once attached, the schema is downloaded and every producer of this station validates
+        /// its messages against it. Waits for the schema up to the request timeout of the client.
+        ///
+        /// If the validator for the schema can not be created, e.g. because the feature of its type
+        /// is not enabled, the schema stays attached and messages are produced without validation.
+        ///
+        /// # Arguments
+        /// * `schema_name` - The name of a schema that already exists in Memphis.
+        pub async fn attach_schema(&self, schema_name: &str) -> Result<(), RequestError> {
+            let subject = format!(
+                "{}{}",
+                MemphisSubscriptions::SchemaUpdatesPrefix,
+                self.get_internal_name(None)
+            );
+
+            // Memphis publishes the attached schema to this subject, so we need to subscribe first.
+            let mut schema_updates = self
+                .memphis_client
+                .get_broker_connection()
+                .subscribe(subject)
+                .await
+                .map_err(|e| RequestError::NatsError(e.into()))?;
+
+            let req = AttachSchemaRequest {
+                schema_name,
+                station_name: &self.options.station_name,
+                username: &self.memphis_client.username,
+            };
+
+            if let Err(e) = self
+                .memphis_client
+                .send_internal_request(&req, MemphisSpecialStation::SchemaAttachments)
+                .await
+            {
+                error!("Failed to attach schema: {}", e);
+                return Err(e);
+            }
+
+            let receive_schema = async {
+                while let Some(message) = schema_updates.next().await {
+                    let update = serde_json::from_slice::<SchemaUpdateMessage>(&message.payload)?;
+                    match update.init {
+                        Some(init) if update.update_type == SCHEMA_UPDATE_TYPE_INIT => {
+                            return Ok(Some(init))
+                        }
+                        _ => continue,
+                    }
+                }
+                Ok::<_, RequestError>(None)
+            };
+            let schema_update = match self.memphis_client.request_timeout {
+                Some(timeout) => tokio::time::timeout(timeout, receive_schema)
+                    .await
+                    .unwrap_or(Ok(None))?,
+                None => receive_schema.await?,
+            };
+            let Some(schema_update) = schema_update else {
+                return Err(RequestError::SchemaError(Box::new(
+                    SchemaValidationError::SchemaNotReceived,
+                )));
+            };
+
+            if let Err(e) = self.install_schema(&schema_update).await {
+                error!(
+                    "Schema {} was attached to station {}, but its validator could not be created. Producing without validation. {}",
+                    schema_name, &self.options.station_name, e
+                );
+            }
+
+            info!(
+                "Attached schema {} to station {}",
+                schema_name, &self.options.station_name
+            );
+
+            Ok(())
+        }
+
+        /// Detaches the schema from the station. Producers stop validating their messages.
+        pub async fn detach_schema(&self) -> Result<(), RequestError> {
+            let req = DetachSchemaRequest {
+                station_name: &self.options.station_name,
+                username: &self.memphis_client.username,
+            };
+
+            if let Err(e) = self
+                .memphis_client
+                .send_internal_request(&req, MemphisSpecialStation::SchemaDetachments)
+                .await
+            {
+                error!("Failed to detach schema: {}", e);
+                return Err(e);
+            }
+
+            *self.schema.write().await = None;
+
+            info!(
+                "Detached schema from station {}",
+                &self.options.station_name
+            );
+
+            Ok(())
+        }
+
+        /// Replaces the validator of the station with one for the given schema.
+        /// See [install_schema](crate::schemaverse::schema_updates::install_schema).
+        pub(crate) async fn install_schema(
+            &self,
+            schema: &SchemaUpdate,
+        ) -> Result<(), SchemaValidationError> {
+            schema_updates::install_schema(&self.schema, schema).await
+        }
+    }
+}
+
 #[cfg(feature = "producers")]
 mod producer {
     use crate::producer::{MemphisProducer, MemphisProducerOptions};
@@ -105,6 +105,7 @@ impl Default for MemphisStationsOptions {
     }
 }
 
+#[allow(clippy::to_string_trait_impl)]
 impl ToString for RetentionType {
     fn to_string(&self) -> String {
         match self {
@@ -117,6 +118,7 @@ impl ToString for RetentionType {
     }
 }
 
+#[allow(clippy::to_string_trait_impl)]
 impl ToString for StorageType {
     fn to_string(&self) -> String {
         match self {
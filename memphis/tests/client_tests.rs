@@ -0,0 +1,205 @@
+use memphis_rust_community::memphis_client::{MemphisClient, MemphisClientOptions};
+use memphis_rust_community::producer::ComposableMessage;
+use std::time::{Duration, Instant};
+use tokio_test::assert_ok;
+
+use crate::common::{
+    create_random_consumer, create_random_producer, create_random_setup, create_random_station,
+    TcpProxy,
+};
+
+mod common;
+
+#[tokio::test]
+async fn connect_with_options() {
+    let _ = env_logger::try_init();
+
+    let options = MemphisClientOptions::new("localhost:6666", "root")
+        .with_password("memphis")
+        .with_server("localhost:6666")
+        .with_connection_timeout(Duration::from_secs(2))
+        .with_request_timeout(Some(Duration::from_secs(10)));
+
+    let client = assert_ok!(MemphisClient::connect(options).await);
+    assert!(client.is_connected());
+
+    let mut events = client.events();
+    assert!(
+        events.try_recv().is_err(),
+        "Only events after subscribing are received."
+    );
+}
+
+#[tokio::test]
+async fn connect_with_missing_credentials_file() {
+    let _ = env_logger::try_init();
+
+    let options = MemphisClientOptions::new("localhost:6666", "root")
+        .with_credentials_file("does-not-exist.creds");
+
+    assert!(MemphisClient::connect(options).await.is_err());
+}
+
+#[test]
+fn credentials_are_not_printed() {
+    let options = MemphisClientOptions::new("localhost:6666", "root").with_password("secret");
+
+    assert!(!format!("{:?}", options).contains("secret"));
+}
+
+#[tokio::test]
+async fn shutdown_waits_for_acks_and_destroys_resources() {
+    let _ = env_logger::try_init();
+    let (client, _station, consumer, mut producer) = create_random_setup().await;
+
+    let mut receiver = consumer.consume().await.unwrap();
+    producer
+        .produce(ComposableMessage::new().with_payload("shutdown"))
+        .await
+        .unwrap()
+        .await
+        .unwrap();
+    let msg = receiver.recv().await.unwrap();
+
+    let ack = tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(500)).await;
+        msg.ack().await
+    });
+    let started = Instant::now();
+    assert_ok!(client.shutdown(Duration::from_secs(5)).await);
+    assert!(
+        started.elapsed() >= Duration::from_millis(500),
+        "Shutdown should wait for the message to be acknowledged."
+    );
+    assert_ok!(ack.await.unwrap());
+
+    assert!(
+        receiver.recv().await.is_none(),
+        "The consumer should be stopped."
+    );
+    assert_ok!(
+        client.shutdown(Duration::from_secs(5)).await,
+        "Nothing is left to destroy."
+    );
+}
+
+#[tokio::test]
+async fn shutdown_skips_dropped_resources() {
+    let _ = env_logger::try_init();
+    let client = common::connect_to_memphis().await;
+    let station = create_random_station(&client).await;
+
+    let consumer = create_random_consumer(&station).await;
+    let consumer_name = consumer.get_internal_name();
+    drop(consumer);
+
+    assert_ok!(client.shutdown(Duration::from_secs(5)).await);
+
+    let stream = assert_ok!(
+        client
+            .get_jetstream_context()
+            .get_stream(station.get_internal_name(Some(1)))
+            .await
+    );
+    assert_ok!(
+        stream.consumer_info(&consumer_name).await,
+        "A dropped consumer should not be destroyed on shutdown."
+    );
+}
+
+#[tokio::test]
+async fn reconnect_registers_only_live_consumers_again() {
+    use memphis_rust_community::memphis_client::MemphisClientEvent;
+
+    let _ = env_logger::try_init();
+    let proxy = TcpProxy::start("localhost:6666").await;
+    let client = assert_ok!(MemphisClient::new(&proxy.address, "root", "memphis", None).await);
+    let station = create_random_station(&client).await;
+
+    let consumer = create_random_consumer(&station).await;
+    let mut receiver = consumer.consume().await.unwrap();
+
+    let dropped = create_random_consumer(&station).await;
+    let dropped_name = dropped.get_internal_name();
+    drop(dropped);
+
+    // Re-registering the dropped consumer would create it again.
+    let stream = assert_ok!(
+        client
+            .get_jetstream_context()
+            .get_stream(station.get_internal_name(Some(1)))
+            .await
+    );
+    assert_ok!(stream.delete_consumer(&dropped_name).await);
+
+    let mut events = client.events();
+    proxy.disconnect_all();
+    let reconnected = tokio::time::timeout(Duration::from_secs(30), async {
+        while events.recv().await.unwrap() != MemphisClientEvent::Connected {}
+    })
+    .await;
+    assert_ok!(reconnected, "The client should reconnect.");
+
+    // The live consumer still receives messages after it was registered again.
+    let mut producer = create_random_producer(&station).await;
+    producer
+        .produce(ComposableMessage::new().with_payload("reconnected"))
+        .await
+        .unwrap()
+        .await
+        .unwrap();
+    let msg = tokio::time::timeout(Duration::from_secs(10), receiver.recv())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(msg.get_data_as_string().unwrap(), "reconnected");
+    msg.ack().await.unwrap();
+
+    assert!(
+        stream.consumer_info(&dropped_name).await.is_err(),
+        "A dropped consumer should not be registered again."
+    );
+}
+
+#[tokio::test]
+async fn dropping_a_producer_clone_keeps_it_registered() {
+    use memphis_rust_community::memphis_client::MemphisClientEvent;
+    use memphis_rust_community::{MemphisServerError, RequestError};
+
+    let _ = env_logger::try_init();
+    let proxy = TcpProxy::start("localhost:6666").await;
+    let client = assert_ok!(MemphisClient::new(&proxy.address, "root", "memphis", None).await);
+    let station = create_random_station(&client).await;
+
+    let producer = create_random_producer(&station).await;
+    let mut survivor = producer.clone();
+    drop(producer);
+
+    let mut events = client.events();
+    proxy.disconnect_all();
+    let reconnected = tokio::time::timeout(Duration::from_secs(30), async {
+        while events.recv().await.unwrap() != MemphisClientEvent::Connected {}
+    })
+    .await;
+    assert_ok!(reconnected, "The client should reconnect.");
+
+    assert_ok!(
+        survivor
+            .produce(ComposableMessage::new().with_payload("reconnected"))
+            .await
+            .unwrap()
+            .await
+    );
+
+    // The survivor is still registered, so shutdown destroys it.
+    assert_ok!(client.shutdown(Duration::from_secs(5)).await);
+    let destroyed = survivor.destroy().await;
+    assert!(
+        matches!(
+            destroyed,
+            Err(RequestError::ServerError(MemphisServerError::NotFound(_)))
+        ),
+        "The producer should have been destroyed on shutdown, got {:?}.",
+        destroyed
+    );
+}
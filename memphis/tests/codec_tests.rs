@@ -0,0 +1,100 @@
+use memphis_rust_community::codec::{decode_with_content_type, Codec, CodecError, JsonCodec};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Payment {
+    id: u64,
+    amount: f64,
+    currency: String,
+    tags: Vec<String>,
+    note: Option<String>,
+}
+
+fn payment() -> Payment {
+    Payment {
+        id: 42,
+        amount: 19.99,
+        currency: "EUR".to_string(),
+        tags: vec!["online".to_string()],
+        note: None,
+    }
+}
+
+fn round_trip<C: Codec>(codec: C) {
+    let payload = codec.encode(&payment()).unwrap();
+    assert_eq!(codec.decode::<Payment>(&payload).unwrap(), payment());
+
+    assert!(codec
+        .decode::<Payment>(&payload[..payload.len() / 2])
+        .is_err());
+}
+
+#[test]
+fn json_codec_round_trip() {
+    round_trip(JsonCodec);
+
+    let payload = JsonCodec.encode(&payment()).unwrap();
+    assert_eq!(
+        std::str::from_utf8(&payload).unwrap(),
+        r#"{"id":42,"amount":19.99,"currency":"EUR","tags":["online"],"note":null}"#
+    );
+    assert_eq!(JsonCodec.content_type(), "application/json");
+}
+
+#[cfg(feature = "codec_msgpack")]
+#[test]
+fn msgpack_codec_round_trip() {
+    use memphis_rust_community::codec::MsgPackCodec;
+
+    round_trip(MsgPackCodec);
+    assert_eq!(MsgPackCodec.content_type(), "application/msgpack");
+}
+
+#[cfg(feature = "codec_cbor")]
+#[test]
+fn cbor_codec_round_trip() {
+    use memphis_rust_community::codec::CborCodec;
+
+    round_trip(CborCodec);
+    assert_eq!(CborCodec.content_type(), "application/cbor");
+}
+
+#[test]
+fn decodes_by_content_type() {
+    let payload = JsonCodec.encode(&payment()).unwrap();
+
+    for content_type in [
+        None,
+        Some("application/json"),
+        Some("Application/JSON; charset=utf-8"),
+    ] {
+        assert_eq!(
+            decode_with_content_type::<Payment>(content_type, &payload).unwrap(),
+            payment()
+        );
+    }
+
+    assert!(matches!(
+        decode_with_content_type::<Payment>(Some("text/plain"), &payload),
+        Err(CodecError::UnsupportedContentType(_))
+    ));
+}
+
+#[cfg(all(feature = "codec_msgpack", feature = "codec_cbor"))]
+#[test]
+fn decodes_binary_codecs_by_content_type() {
+    use memphis_rust_community::codec::{CborCodec, MsgPackCodec};
+
+    let payload = MsgPackCodec.encode(&payment()).unwrap();
+    assert_eq!(
+        decode_with_content_type::<Payment>(Some(MsgPackCodec.content_type()), &payload).unwrap(),
+        payment()
+    );
+    assert!(decode_with_content_type::<Payment>(Some(CborCodec.content_type()), &payload).is_err());
+
+    let payload = CborCodec.encode(&payment()).unwrap();
+    assert_eq!(
+        decode_with_content_type::<Payment>(Some(CborCodec.content_type()), &payload).unwrap(),
+        payment()
+    );
+}
@@ -3,7 +3,10 @@ use memphis_rust_community::consumer::{MemphisConsumer, MemphisConsumerOptions};
 use memphis_rust_community::memphis_client::MemphisClient;
 use memphis_rust_community::producer::{MemphisProducer, MemphisProducerOptions};
 use memphis_rust_community::station::{MemphisStation, MemphisStationsOptions, StorageType};
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
+use tokio::net::{TcpListener, TcpStream};
+use tokio::task::AbortHandle;
 use tokio_test::assert_ok;
 
 #[allow(dead_code)]
@@ -67,3 +70,75 @@ pub async fn create_random_setup() -> (
 
     (client, station, consumer, producer)
 }
+
+/// Creates a JSON schema in Memphis and returns its name.
+#[cfg(feature = "schemaverse")]
+#[allow(dead_code)]
+pub async fn create_random_json_schema(client: &MemphisClient, schema_content: &str) -> String {
+    let schema_name = uuid::Uuid::new_v4().to_string();
+    let request = serde_json::json!({
+        "name": &schema_name,
+        "type": "json",
+        "created_by_username": "root",
+        "schema_content": schema_content,
+        "message_struct_name": "",
+    });
+
+    let response = client
+        .get_broker_connection()
+        .request(
+            "$memphis_schema_creations".to_string(),
+            serde_json::to_vec(&request).unwrap().into(),
+        )
+        .await;
+    let response = assert_ok!(response, "Creating a schema should be possible.");
+
+    // Memphis answers with an empty payload or a JSON object with an empty `err`.
+    if !response.payload.is_empty() {
+        let response: serde_json::Value = serde_json::from_slice(&response.payload).unwrap();
+        let error = response["err"].as_str().unwrap_or_default();
+        assert!(error.is_empty(), "Creating a schema failed: {}", error);
+    }
+
+    schema_name
+}
+
+/// Forwards the connections of clients to Memphis, so tests can cut them to force a reconnect.
+#[allow(dead_code)]
+pub struct TcpProxy {
+    pub address: String,
+    connections: Arc<Mutex<Vec<AbortHandle>>>,
+}
+
+#[allow(dead_code)]
+impl TcpProxy {
+    pub async fn start(target: &'static str) -> Self {
+        let listener = assert_ok!(TcpListener::bind("127.0.0.1:0").await);
+        let address = assert_ok!(listener.local_addr()).to_string();
+        let connections = Arc::new(Mutex::new(Vec::<AbortHandle>::new()));
+
+        let accepted = connections.clone();
+        tokio::spawn(async move {
+            while let Ok((mut inbound, _)) = listener.accept().await {
+                let connection = tokio::spawn(async move {
+                    if let Ok(mut outbound) = TcpStream::connect(target).await {
+                        let _ = tokio::io::copy_bidirectional(&mut inbound, &mut outbound).await;
+                    }
+                });
+                accepted.lock().unwrap().push(connection.abort_handle());
+            }
+        });
+
+        Self {
+            address,
+            connections,
+        }
+    }
+
+    /// Closes all connections. The clients reconnect through the proxy.
+    pub fn disconnect_all(&self) {
+        for connection in self.connections.lock().unwrap().drain(..) {
+            connection.abort();
+        }
+    }
+}
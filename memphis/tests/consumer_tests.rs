@@ -88,3 +88,323 @@ async fn destroy_consumer() {
         }
     };
 }
+
+#[tokio::test]
+async fn consume_stream_with_backpressure() {
+    use futures_util::StreamExt;
+
+    let _ = env_logger::try_init();
+
+    let client = connect_to_memphis().await;
+    let station = create_random_station(&client).await;
+    let consumer = assert_ok!(
+        station
+            .create_consumer(
+                MemphisConsumerOptions::new(&uuid::Uuid::new_v4().to_string())
+                    .with_batch_size(2)
+                    .with_max_ack_time(Duration::from_secs(10))
+            )
+            .await
+    );
+    let mut messages = consumer.consume_stream().await.unwrap();
+
+    let mut producer = create_random_producer(&station).await;
+    for i in 0..10 {
+        let msg = ComposableMessage::new().with_payload(i.to_string());
+        producer.produce(msg).await.unwrap().await.unwrap();
+    }
+
+    // Messages are received in order, even though only two of them are buffered at a time.
+    for i in 0..10 {
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        let msg = tokio::time::timeout(Duration::from_secs(10), messages.next())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(msg.get_data_as_string().unwrap(), i.to_string());
+        msg.ack().await.unwrap();
+    }
+}
+
+#[tokio::test]
+async fn consume_stream_buffers_at_most_one_batch_while_stalled() {
+    let _ = env_logger::try_init();
+
+    let batch_size = 2;
+    let client = connect_to_memphis().await;
+    let station = create_random_station(&client).await;
+    let consumer = assert_ok!(
+        station
+            .create_consumer(
+                MemphisConsumerOptions::new(&uuid::Uuid::new_v4().to_string())
+                    .with_batch_size(batch_size)
+                    .with_batch_max_time_to_wait(Duration::from_millis(100))
+                    .with_max_ack_time(Duration::from_secs(30))
+            )
+            .await
+    );
+
+    let mut producer = create_random_producer(&station).await;
+    for i in 0..20 {
+        let msg = ComposableMessage::new().with_payload(i.to_string());
+        producer.produce(msg).await.unwrap().await.unwrap();
+    }
+
+    // Nothing is read from the stream, so the pull task blocks once the channel is full.
+    let _messages = consumer.consume_stream().await.unwrap();
+    tokio::time::sleep(Duration::from_secs(3)).await;
+
+    let stream = assert_ok!(
+        client
+            .get_jetstream_context()
+            .get_stream(station.get_internal_name(None))
+            .await
+    );
+    let mut jetstream_consumer = assert_ok!(
+        stream
+            .get_consumer::<async_nats::jetstream::consumer::pull::Config>(
+                &consumer.get_internal_name()
+            )
+            .await
+    );
+    let consumer_info = assert_ok!(jetstream_consumer.info().await);
+
+    // The channel holds `batch_size` messages, the pull task holds at most one more batch.
+    let channel_bound = batch_size;
+    assert!(consumer_info.num_ack_pending > 0);
+    assert!(
+        consumer_info.num_ack_pending <= channel_bound + batch_size,
+        "{} messages were pulled while the receiver was stalled",
+        consumer_info.num_ack_pending
+    );
+}
+
+#[tokio::test]
+async fn fetch_rotates_over_partitions() {
+    use memphis_rust_community::station::MemphisStationsOptions;
+
+    let _ = env_logger::try_init();
+
+    let client = connect_to_memphis().await;
+    let station = assert_ok!(
+        client
+            .create_station(
+                MemphisStationsOptions::new(&uuid::Uuid::new_v4().to_string())
+                    .with_partition_number(3)
+            )
+            .await
+    );
+    let consumer = create_random_consumer(&station).await;
+
+    let producer = create_random_producer(&station).await;
+    for partition in 1..=3 {
+        let msg = ComposableMessage::new().with_payload(partition.to_string());
+        producer
+            .produce_to_partition(Some(partition), msg)
+            .await
+            .unwrap()
+            .await
+            .unwrap();
+    }
+
+    // Fetching one message at a time still reaches every partition.
+    let mut received = Vec::new();
+    for _ in 0..3 {
+        let messages = assert_ok!(consumer.fetch(1, Duration::from_secs(2)).await);
+        for msg in messages {
+            received.push(msg.get_data_as_string().unwrap());
+            msg.ack().await.unwrap();
+        }
+    }
+    received.sort();
+    assert_eq!(received, vec!["1", "2", "3"]);
+}
+
+#[tokio::test]
+async fn run_message_handler() {
+    use memphis_rust_community::consumer::{Ack, MemphisMessage, MessageHandler, Nack};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    struct CountingHandler {
+        handled: Arc<AtomicUsize>,
+    }
+
+    #[async_trait::async_trait]
+    impl MessageHandler for CountingHandler {
+        async fn handle(&self, message: MemphisMessage) -> Result<Ack, Nack> {
+            self.handled.fetch_add(1, Ordering::SeqCst);
+            // The first delivery of this message fails, the retry succeeds.
+            if message.get_data_as_string().unwrap() == "retry" && message.get_delivery_count() == 1
+            {
+                return Err(Nack::RetryAfter(Duration::from_millis(100)));
+            }
+            Ok(Ack)
+        }
+    }
+
+    let _ = env_logger::try_init();
+
+    let client = connect_to_memphis().await;
+    let station = create_random_station(&client).await;
+    let consumer = Arc::new(create_random_consumer(&station).await);
+
+    let handled = Arc::new(AtomicUsize::new(0));
+    let handler = CountingHandler {
+        handled: handled.clone(),
+    };
+    let running_consumer = consumer.clone();
+    let run = tokio::spawn(async move { running_consumer.run(handler, 4).await });
+
+    let mut producer = create_random_producer(&station).await;
+    for payload in ["a", "retry", "b"] {
+        let msg = ComposableMessage::new().with_payload(payload);
+        producer.produce(msg).await.unwrap().await.unwrap();
+    }
+
+    tokio::time::timeout(Duration::from_secs(10), async {
+        while handled.load(Ordering::SeqCst) < 4 {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+    })
+    .await
+    .unwrap();
+
+    consumer.stop();
+    assert_ok!(run.await.unwrap());
+    assert_eq!(handled.load(Ordering::SeqCst), 4);
+}
+
+#[tokio::test]
+async fn consumer_emits_stopped_event() {
+    use memphis_rust_community::consumer::MemphisEvent;
+
+    let _ = env_logger::try_init();
+
+    let client = connect_to_memphis().await;
+    let station = create_random_station(&client).await;
+    let consumer = create_random_consumer(&station).await;
+    let mut events = consumer.subscribe_events();
+
+    let _receiver = consumer.consume().await.unwrap();
+    consumer.stop();
+    consumer.stop();
+
+    let event = tokio::time::timeout(Duration::from_secs(1), events.recv())
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(matches!(event, MemphisEvent::ConsumerStopped));
+    assert!(
+        events.try_recv().is_err(),
+        "Stopping twice emits one event."
+    );
+}
+
+#[tokio::test]
+async fn dropped_consumer_stops_its_tasks() {
+    use memphis_rust_community::consumer::MemphisEvent;
+
+    let _ = env_logger::try_init();
+
+    let client = connect_to_memphis().await;
+    let station = create_random_station(&client).await;
+    let consumer = create_random_consumer(&station).await;
+    let mut events = consumer.subscribe_events();
+    let mut receiver = consumer.consume().await.unwrap();
+
+    drop(consumer);
+
+    let event = tokio::time::timeout(Duration::from_secs(1), events.recv())
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(matches!(event, MemphisEvent::ConsumerStopped));
+    let closed = tokio::time::timeout(Duration::from_secs(10), receiver.recv()).await;
+    assert!(
+        matches!(closed, Ok(None)),
+        "The receiver closes once the pull subscriptions of a dropped consumer ended."
+    );
+}
+
+#[tokio::test]
+async fn consumer_recovers_after_being_deleted() {
+    use memphis_rust_community::consumer::{ConsumerRecoveryOptions, MemphisEvent};
+
+    let _ = env_logger::try_init();
+
+    let client = connect_to_memphis().await;
+    let station = create_random_station(&client).await;
+    let consumer = assert_ok!(
+        station
+            .create_consumer(
+                MemphisConsumerOptions::new(&uuid::Uuid::new_v4().to_string())
+                    .with_ping_interval(Duration::from_millis(500))
+                    .with_recovery(
+                        ConsumerRecoveryOptions::default()
+                            .with_initial_backoff(Duration::from_millis(100))
+                    )
+            )
+            .await
+    );
+    let mut events = consumer.subscribe_events();
+    let mut receiver = consumer.consume().await.unwrap();
+
+    let stream = client
+        .get_jetstream_context()
+        .get_stream(station.get_internal_name(Some(1)))
+        .await
+        .unwrap();
+    stream
+        .delete_consumer(&consumer.get_internal_name())
+        .await
+        .unwrap();
+
+    let recovered = tokio::time::timeout(Duration::from_secs(10), async {
+        loop {
+            if let MemphisEvent::ConsumerRecovered = events.recv().await.unwrap() {
+                break;
+            }
+        }
+    })
+    .await;
+    assert_ok!(recovered, "The consumer should be recovered.");
+
+    let mut producer = create_random_producer(&station).await;
+    let msg = ComposableMessage::new().with_payload("recovered");
+    producer.produce(msg).await.unwrap().await.unwrap();
+
+    let msg = tokio::time::timeout(Duration::from_secs(10), receiver.recv())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(msg.get_data_as_string().unwrap(), "recovered");
+}
+
+#[tokio::test]
+async fn fetch_messages() {
+    let _ = env_logger::try_init();
+
+    let client = connect_to_memphis().await;
+    let station = create_random_station(&client).await;
+    let consumer = create_random_consumer(&station).await;
+
+    let mut producer = create_random_producer(&station).await;
+    for i in 0..5 {
+        let msg = ComposableMessage::new().with_payload(i.to_string());
+        producer.produce(msg).await.unwrap().await.unwrap();
+    }
+
+    let messages = assert_ok!(consumer.fetch(3, Duration::from_secs(5)).await);
+    assert_eq!(messages.len(), 3);
+    for msg in messages {
+        msg.ack().await.unwrap();
+    }
+
+    // Returns the remaining messages once the timeout has expired.
+    let messages = assert_ok!(consumer.fetch(10, Duration::from_secs(2)).await);
+    assert_eq!(messages.len(), 2);
+    for msg in messages {
+        msg.ack().await.unwrap();
+    }
+}
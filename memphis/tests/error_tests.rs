@@ -0,0 +1,23 @@
+use memphis_rust_community::consumer::ConsumerError;
+use memphis_rust_community::producer::ProducerError;
+use memphis_rust_community::{MemphisError, MemphisServerError, RequestError};
+
+#[test]
+fn retryable_errors() {
+    let unavailable = RequestError::ServerError(MemphisServerError::Unavailable(
+        "Cluster is not ready, try again".to_string(),
+    ));
+    assert!(MemphisError::from(unavailable).is_retryable());
+    assert!(MemphisError::from(RequestError::NotConnected).is_retryable());
+    assert!(
+        MemphisError::from(ConsumerError::RequestError(RequestError::NotConnected)).is_retryable()
+    );
+    assert!(MemphisError::from(ProducerError::PartitionUnavailable).is_retryable());
+
+    let not_found = RequestError::ServerError(MemphisServerError::StationNotFound(
+        "Station orders does not exist".to_string(),
+    ));
+    assert!(!MemphisError::from(not_found).is_retryable());
+    assert!(!MemphisError::from(ConsumerError::InvalidSequence).is_retryable());
+    assert!(!MemphisError::from(ProducerError::PayloadEmpty).is_retryable());
+}
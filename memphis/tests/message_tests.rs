@@ -95,6 +95,61 @@ async fn message_resend_test() {
     handle.abort();
 }
 
+#[tokio::test]
+async fn dls_message_replay_test() {
+    use memphis_rust_community::consumer::DlsMessageKind;
+
+    let _ = env_logger::try_init();
+
+    let client = connect_to_memphis().await;
+    let station = create_random_station(&client).await;
+
+    let consumer = create_random_consumer(&station).await;
+    let mut receiver = consumer.consume().await.unwrap();
+    let mut dls_receiver = consumer.consume_dls().await.unwrap();
+
+    let mut producer = create_random_producer(&station).await;
+    let payload = "This should be replayed!";
+    producer
+        .produce(
+            ComposableMessage::new()
+                .with_payload(payload)
+                .with_header("origin", "test"),
+        )
+        .await
+        .unwrap();
+
+    // Never acknowledged, so the message is sent to the DLS after two deliveries.
+    for _ in 0..2 {
+        let msg = receiver.recv().await.unwrap();
+        msg.disable_missed_ack_safety().await;
+    }
+
+    let dls_message = tokio::time::timeout(Duration::from_secs(30), dls_receiver.recv())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(dls_message.kind(), DlsMessageKind::Poison);
+    assert_eq!(dls_message.payload().as_ref(), payload.as_bytes());
+    assert_eq!(
+        dls_message.producer().map(|p| p.name.clone()),
+        Some(producer.get_name())
+    );
+
+    dls_message
+        .replay_to_station(&mut producer)
+        .await
+        .unwrap()
+        .await
+        .unwrap();
+
+    let msg = receiver.recv().await.unwrap();
+    assert_eq!(msg.get_data_as_string().unwrap(), payload);
+    let headers = msg.get_headers().as_ref().unwrap();
+    assert_eq!(headers.get("origin").unwrap().as_str(), "test");
+    msg.ack().await.unwrap();
+}
+
 #[tokio::test]
 async fn message_delay_test() {
     let _ = env_logger::try_init();
@@ -141,6 +196,116 @@ async fn message_delay_test() {
     }
 }
 
+#[tokio::test]
+async fn message_nack_test() {
+    let _ = env_logger::try_init();
+
+    let (_, _station, consumer, mut producer) = create_random_setup().await;
+
+    let payload = "This should be redelivered!";
+    producer
+        .produce(ComposableMessage::new().with_payload(payload))
+        .await
+        .unwrap();
+
+    let mut receiver = consumer.consume().await.unwrap();
+    let msg = receiver.recv().await.unwrap();
+    assert_ok!(msg.nack().await, "Nacking a Message should be possible.");
+
+    // Redelivered before the max ack time of 5 seconds is exceeded.
+    let msg = tokio::time::timeout(Duration::from_secs(3), receiver.recv())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(msg.get_data_as_string().unwrap(), payload);
+    assert_eq!(msg.get_delivery_count(), 2);
+    msg.ack().await.unwrap();
+}
+
+#[tokio::test]
+async fn message_term_test() {
+    let _ = env_logger::try_init();
+
+    let (_, _station, consumer, mut producer) = create_random_setup().await;
+
+    producer
+        .produce(ComposableMessage::new().with_payload("This should not be redelivered!"))
+        .await
+        .unwrap();
+
+    let mut receiver = consumer.consume().await.unwrap();
+    let msg = receiver.recv().await.unwrap();
+    assert_ok!(
+        msg.in_progress().await,
+        "Sending a progress ack should be possible."
+    );
+    assert_ok!(
+        msg.term().await,
+        "Terminating a Message should be possible."
+    );
+
+    tokio::time::sleep(Duration::from_secs(7)).await;
+    assert_eq!(receiver.try_recv().unwrap_err(), TryRecvError::Empty);
+}
+
+#[tokio::test]
+async fn message_metadata_test() {
+    let _ = env_logger::try_init();
+
+    let (_, _station, consumer, mut producer) = create_random_setup().await;
+
+    for i in 0..2 {
+        producer
+            .produce(ComposableMessage::new().with_payload(i.to_string()))
+            .await
+            .unwrap();
+    }
+
+    let mut receiver = consumer.consume().await.unwrap();
+    let msg = receiver.recv().await.unwrap();
+    let metadata = assert_ok!(msg.metadata());
+
+    assert_eq!(metadata.stream_sequence, 1);
+    assert_eq!(metadata.consumer_sequence, 1);
+    assert_eq!(metadata.delivery_count, 1);
+    assert_eq!(metadata.partition, Some(1));
+    assert_eq!(metadata.producer_name, Some(producer.get_name()));
+    assert!(metadata.connection_id.is_some());
+    assert!(metadata.published <= std::time::SystemTime::now());
+    msg.ack().await.unwrap();
+}
+
+#[tokio::test]
+async fn progress_acks_prevent_redelivery() {
+    let _ = env_logger::try_init();
+
+    let (_, _station, consumer, mut producer) = create_random_setup().await;
+
+    for i in 0..50 {
+        producer
+            .produce(ComposableMessage::new().with_payload(i.to_string()))
+            .await
+            .unwrap();
+    }
+
+    let mut receiver = consumer.consume().await.unwrap();
+    let mut messages = Vec::new();
+    for _ in 0..50 {
+        let msg = receiver.recv().await.unwrap();
+        // Dropping a clone keeps sending progress acks for the message.
+        drop(msg.clone());
+        messages.push(msg);
+    }
+
+    // The max ack time of 5 seconds is exceeded, but the messages are still in progress.
+    tokio::time::sleep(Duration::from_secs(12)).await;
+    assert_eq!(receiver.try_recv().unwrap_err(), TryRecvError::Empty);
+
+    for msg in messages {
+        msg.ack().await.unwrap();
+    }
+}
+
 #[tokio::test]
 async fn max_messages_test_one_partition() {
     let _ = env_logger::try_init();
@@ -333,6 +498,62 @@ async fn partition_sending_receiving() {
     assert_err!(receiver.try_recv());
 }
 
+#[tokio::test]
+async fn partition_key_keeps_order() {
+    let _ = env_logger::try_init();
+    let random_station_name = uuid::Uuid::new_v4().to_string();
+
+    eprintln!("random_station_name: {}", random_station_name);
+
+    let client = connect_to_memphis().await;
+    let station = assert_ok!(
+        client
+            .create_station(
+                MemphisStationsOptions::new(&random_station_name)
+                    .with_storage_type(Memory)
+                    .with_partition_number(10)
+            )
+            .await
+    );
+
+    let consumer = assert_ok!(
+        station
+            .create_consumer(MemphisConsumerOptions::new("consumer"))
+            .await
+    );
+
+    let mut producer = assert_ok!(
+        station
+            .create_producer(MemphisProducerOptions::new("producer"))
+            .await
+    );
+
+    for x in 0..20 {
+        let message = ComposableMessage::new().with_payload(format!("Message {}", x));
+        if x % 2 == 0 {
+            assert_ok!(producer.produce_with_key("customer-1", message).await);
+        } else {
+            assert_ok!(
+                producer
+                    .produce(message.with_partition_key("customer-1"))
+                    .await
+            );
+        }
+    }
+
+    let mut receiver = assert_ok!(consumer.consume().await);
+
+    for x in 0..20 {
+        let msg = receiver.recv().await.unwrap();
+        assert_eq!(
+            msg.get_data_as_string().unwrap(),
+            format!("Message {}", x),
+            "Messages with the same partition key should keep their order."
+        );
+        assert_ok!(msg.ack().await);
+    }
+}
+
 #[tokio::test]
 async fn no_message_duplication() {
     let _ = env_logger::try_init();
@@ -356,3 +577,77 @@ async fn no_message_duplication() {
 }
 
 //TODO: Test for Messages in DLS once Memphis automatically resends them.
+
+#[tokio::test]
+async fn consumer_groups_receive_the_same_message() {
+    let _ = env_logger::try_init();
+
+    let client = connect_to_memphis().await;
+    let station = create_random_station(&client).await;
+    let first = create_random_consumer(&station).await;
+    let second = create_random_consumer(&station).await;
+
+    let mut first_receiver = first.consume().await.unwrap();
+    let mut second_receiver = second.consume().await.unwrap();
+
+    let mut producer = create_random_producer(&station).await;
+    let payload = "Some message";
+    producer
+        .produce(ComposableMessage::new().with_payload(payload))
+        .await
+        .unwrap();
+
+    for receiver in [&mut first_receiver, &mut second_receiver] {
+        let msg = tokio::time::timeout(Duration::from_secs(5), receiver.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(msg.get_data_as_string().unwrap().as_str(), payload);
+        msg.ack().await.unwrap();
+    }
+}
+
+#[tokio::test]
+async fn typed_consumer_routes_poison_messages() {
+    use memphis_rust_community::consumer::TypedConsumer;
+    use memphis_rust_community::producer::TypedProducer;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Payment {
+        id: u64,
+        amount: f64,
+    }
+
+    let _ = env_logger::try_init();
+
+    let client = connect_to_memphis().await;
+    let station = create_random_station(&client).await;
+
+    let (poison_sender, mut poison_receiver) = tokio::sync::mpsc::unbounded_channel();
+    let consumer = TypedConsumer::<Payment>::new(create_random_consumer(&station).await)
+        .with_poison_handler(move |msg, _e| {
+            poison_sender
+                .send(msg.get_data_as_string().unwrap())
+                .unwrap();
+        });
+    let mut receiver = consumer.consume().await.unwrap();
+
+    let mut producer = create_random_producer(&station).await;
+    producer
+        .produce(ComposableMessage::new().with_payload("not a payment"))
+        .await
+        .unwrap()
+        .await
+        .unwrap();
+
+    let mut producer = TypedProducer::<Payment>::new(producer);
+    let payment = Payment { id: 1, amount: 2.5 };
+    producer.produce(&payment).await.unwrap().await.unwrap();
+
+    let (received, msg) = receiver.recv().await.unwrap();
+    assert_eq!(received, payment);
+    msg.ack().await.unwrap();
+
+    assert_eq!(poison_receiver.recv().await.unwrap(), "not a payment");
+}
@@ -0,0 +1,144 @@
+use std::collections::HashSet;
+
+use memphis_rust_community::producer::{
+    get_partition_from_key, ComposableMessage, HeaderStrategy, LeastRecentlyUsedStrategy,
+    PartitionStrategy, RandomStrategy, RoundRobinStrategy, StickyStrategy,
+};
+
+// The expected values were computed with the reference MurmurHash3_x86_32 implementation
+// using seed 31, which is what the Go, Node.js and Python SDKs use.
+const PARTITIONS: [u32; 10] = [1, 2, 3, 4, 5, 6, 7, 8, 9, 10];
+
+#[test]
+fn partition_key_matches_other_sdks() {
+    let cases = [
+        ("", 2),
+        ("a", 6),
+        ("customer-1", 8),
+        ("customer-42", 5),
+        ("order-7f3a", 9),
+        ("memphis", 7),
+        ("hello world", 9),
+    ];
+
+    for (key, expected) in cases {
+        assert_eq!(
+            get_partition_from_key(key, &PARTITIONS),
+            Some(expected),
+            "Key '{}' should be mapped to partition {}",
+            key,
+            expected
+        );
+    }
+}
+
+#[test]
+fn partition_key_uses_partition_list() {
+    // The hash selects an index into the partition list, not the partition number itself.
+    assert_eq!(get_partition_from_key("customer-1", &[7, 11, 13]), Some(7));
+    assert_eq!(get_partition_from_key("order-7f3a", &[7, 11, 13]), Some(11));
+    assert_eq!(get_partition_from_key("", &[7, 11, 13]), Some(13));
+}
+
+#[test]
+fn partition_key_is_stable() {
+    let first = get_partition_from_key("customer-1", &PARTITIONS);
+    for _ in 0..100 {
+        assert_eq!(get_partition_from_key("customer-1", &PARTITIONS), first);
+    }
+}
+
+#[test]
+fn partition_key_without_partitions() {
+    assert_eq!(get_partition_from_key("customer-1", &[]), None);
+}
+
+#[test]
+fn round_robin_strategy_visits_all_partitions() {
+    let mut strategy = RoundRobinStrategy::new();
+    let message = ComposableMessage::new();
+
+    let selected: Vec<u32> = (0..6)
+        .map(|_| strategy.select_partition(&[1, 2, 3], &message).unwrap())
+        .collect();
+
+    assert_eq!(selected, vec![2, 3, 1, 2, 3, 1]);
+}
+
+#[test]
+fn random_strategy_stays_in_partition_list() {
+    let mut strategy = RandomStrategy::new();
+    let message = ComposableMessage::new();
+
+    for _ in 0..100 {
+        let partition = strategy.select_partition(&[4, 5, 6], &message).unwrap();
+        assert!([4, 5, 6].contains(&partition));
+    }
+}
+
+#[test]
+fn sticky_strategy_switches_after_batch() {
+    let message = ComposableMessage::new();
+
+    let mut strategy = StickyStrategy::new();
+    let first = strategy.select_partition(&PARTITIONS, &message);
+    for _ in 0..100 {
+        assert_eq!(strategy.select_partition(&PARTITIONS, &message), first);
+    }
+
+    let mut strategy = StickyStrategy::with_batch_size(5);
+    let batch: HashSet<u32> = (0..5)
+        .map(|_| strategy.select_partition(&PARTITIONS, &message).unwrap())
+        .collect();
+    assert_eq!(batch.len(), 1, "A batch should stay on one partition.");
+
+    // The current partition disappeared, so a new one has to be chosen.
+    let current = *batch.iter().next().unwrap();
+    let remaining: Vec<u32> = PARTITIONS.into_iter().filter(|p| *p != current).collect();
+    let partition = strategy.select_partition(&remaining, &message).unwrap();
+    assert_ne!(partition, current);
+}
+
+#[test]
+fn least_recently_used_strategy_spreads_evenly() {
+    let mut strategy = LeastRecentlyUsedStrategy::new();
+    let message = ComposableMessage::new();
+
+    let selected: HashSet<u32> = (0..3)
+        .map(|_| strategy.select_partition(&[1, 2, 3], &message).unwrap())
+        .collect();
+    assert_eq!(selected.len(), 3);
+
+    // Partition 4 was never used, so it has to be next.
+    assert_eq!(strategy.select_partition(&[1, 2, 3, 4], &message), Some(4));
+}
+
+#[test]
+fn least_recently_used_strategy_forgets_removed_partitions() {
+    let mut strategy = LeastRecentlyUsedStrategy::new();
+    let message = ComposableMessage::new();
+
+    for _ in 0..3 {
+        strategy.select_partition(&[1, 2, 3], &message).unwrap();
+    }
+    assert_eq!(strategy.select_partition(&[1, 2], &message), Some(1));
+
+    // Partition 3 was removed in between, so it counts as never used.
+    assert_eq!(strategy.select_partition(&[1, 2, 3], &message), Some(3));
+}
+
+#[test]
+fn header_strategy_hashes_header_value() {
+    let mut strategy = HeaderStrategy::new("customer-id").with_fallback(
+        |partitions: &[u32], _message: &ComposableMessage| partitions.last().copied(),
+    );
+
+    let message = ComposableMessage::new().with_header("customer-id", "customer-1");
+    assert_eq!(
+        strategy.select_partition(&PARTITIONS, &message),
+        get_partition_from_key("customer-1", &PARTITIONS)
+    );
+
+    let message = ComposableMessage::new();
+    assert_eq!(strategy.select_partition(&PARTITIONS, &message), Some(10));
+}
@@ -1,4 +1,9 @@
-use crate::common::{connect_to_memphis, create_random_producer, create_random_station};
+use memphis_rust_community::producer::TypedProducer;
+use serde::Serialize;
+
+use crate::common::{
+    connect_to_memphis, create_random_consumer, create_random_producer, create_random_station,
+};
 
 mod common;
 
@@ -9,3 +14,43 @@ async fn create_producer() {
     let station = create_random_station(&client).await;
     let _producer = create_random_producer(&station).await;
 }
+
+#[derive(Serialize)]
+struct Payment {
+    id: u64,
+    amount: f64,
+}
+
+#[tokio::test]
+async fn typed_producer_sets_content_type() {
+    let _ = env_logger::try_init();
+    let client = connect_to_memphis().await;
+    let station = create_random_station(&client).await;
+    let consumer = create_random_consumer(&station).await;
+    let mut receiver = consumer.consume().await.unwrap();
+
+    let producer = create_random_producer(&station).await;
+    let mut producer = TypedProducer::<Payment>::new(producer);
+    producer
+        .produce(&Payment { id: 1, amount: 9.5 })
+        .await
+        .unwrap()
+        .await
+        .unwrap();
+
+    let msg = receiver.recv().await.unwrap();
+    assert_eq!(
+        msg.get_data_as_string().unwrap(),
+        r#"{"id":1,"amount":9.5}"#
+    );
+    assert_eq!(
+        msg.get_headers()
+            .clone()
+            .unwrap()
+            .get("content-type")
+            .unwrap()
+            .as_str(),
+        "application/json"
+    );
+    msg.ack().await.unwrap();
+}
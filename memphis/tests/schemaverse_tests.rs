@@ -0,0 +1,420 @@
+#![cfg(feature = "schemaverse")]
+
+#[cfg(feature = "validator_protobuf")]
+mod protobuf {
+    use bytes::Bytes;
+    use memphis_rust_community::schemaverse::schema::protobuf::ProtobufSchemaValidator;
+    use memphis_rust_community::schemaverse::schema::SchemaValidator;
+    use prost_reflect::prost::Message;
+    use prost_reflect::prost_types::field_descriptor_proto::{Label, Type};
+    use prost_reflect::prost_types::{
+        DescriptorProto, EnumDescriptorProto, EnumValueDescriptorProto, FieldDescriptorProto,
+        FileDescriptorProto, FileDescriptorSet,
+    };
+
+    fn field(
+        name: &str,
+        number: i32,
+        field_type: Type,
+        type_name: Option<&str>,
+    ) -> FieldDescriptorProto {
+        FieldDescriptorProto {
+            name: Some(name.to_string()),
+            number: Some(number),
+            label: Some(Label::Optional as i32),
+            r#type: Some(field_type as i32),
+            type_name: type_name.map(str::to_string),
+            ..Default::default()
+        }
+    }
+
+    /// The descriptor set Memphis compiles for:
+    ///
+    /// ```proto
+    /// syntax = "proto3";
+    /// package memphis.test;
+    ///
+    /// message Test {
+    ///     string name = 1;
+    ///     int32 age = 2;
+    ///     Status status = 3;
+    ///     Address address = 4;
+    ///
+    ///     message Address {
+    ///         string street = 1;
+    ///     }
+    /// }
+    ///
+    /// enum Status {
+    ///     UNKNOWN = 0;
+    ///     ACTIVE = 1;
+    /// }
+    /// ```
+    fn descriptor_set(status_type: &str) -> Vec<u8> {
+        let file = FileDescriptorProto {
+            name: Some("test.proto".to_string()),
+            package: Some("memphis.test".to_string()),
+            syntax: Some("proto3".to_string()),
+            message_type: vec![DescriptorProto {
+                name: Some("Test".to_string()),
+                field: vec![
+                    field("name", 1, Type::String, None),
+                    field("age", 2, Type::Int32, None),
+                    field("status", 3, Type::Enum, Some(status_type)),
+                    field(
+                        "address",
+                        4,
+                        Type::Message,
+                        Some(".memphis.test.Test.Address"),
+                    ),
+                ],
+                nested_type: vec![DescriptorProto {
+                    name: Some("Address".to_string()),
+                    field: vec![field("street", 1, Type::String, None)],
+                    ..Default::default()
+                }],
+                ..Default::default()
+            }],
+            enum_type: vec![EnumDescriptorProto {
+                name: Some("Status".to_string()),
+                value: ["UNKNOWN", "ACTIVE"]
+                    .iter()
+                    .zip(0..)
+                    .map(|(name, number)| EnumValueDescriptorProto {
+                        name: Some(name.to_string()),
+                        number: Some(number),
+                        ..Default::default()
+                    })
+                    .collect(),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+
+        FileDescriptorSet { file: vec![file] }.encode_to_vec()
+    }
+
+    fn validator() -> ProtobufSchemaValidator {
+        ProtobufSchemaValidator::from_descriptor_set(
+            &descriptor_set(".memphis.test.Status"),
+            Some("Test"),
+        )
+        .unwrap()
+    }
+
+    // name = "abc", age = 5
+    const VALID_MESSAGE: &[u8] = &[0x0a, 0x03, b'a', b'b', b'c', 0x10, 0x05];
+
+    #[test]
+    fn validates_message_against_descriptor_set() {
+        let validator = validator();
+
+        assert!(validator
+            .validate(&Bytes::from_static(VALID_MESSAGE))
+            .is_ok());
+        assert!(validator.validate(&Bytes::new()).is_ok());
+    }
+
+    #[test]
+    fn rejects_truncated_message() {
+        let truncated = Bytes::from_static(&VALID_MESSAGE[..4]);
+        assert!(validator().validate(&truncated).is_err());
+    }
+
+    #[test]
+    fn rejects_wrong_wire_type() {
+        // Field 1 encoded as a varint, but it is a string.
+        let message = Bytes::from_static(&[0x08, 0x01]);
+        assert!(validator().validate(&message).is_err());
+    }
+
+    #[test]
+    fn resolves_message_names() {
+        let descriptor = descriptor_set(".memphis.test.Status");
+
+        let validator =
+            ProtobufSchemaValidator::from_descriptor_set(&descriptor, Some("memphis.test.Test"))
+                .unwrap();
+        assert_eq!(
+            validator.message_descriptor().full_name(),
+            "memphis.test.Test"
+        );
+
+        let validator = ProtobufSchemaValidator::from_descriptor_set(&descriptor, None).unwrap();
+        assert_eq!(
+            validator.message_descriptor().full_name(),
+            "memphis.test.Test"
+        );
+
+        assert!(
+            ProtobufSchemaValidator::from_descriptor_set(&descriptor, Some("Missing")).is_err()
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_descriptor_set() {
+        assert!(ProtobufSchemaValidator::from_descriptor_set(&[], None).is_err());
+        assert!(ProtobufSchemaValidator::from_descriptor_set(&[0xff, 0xff], None).is_err());
+        assert!(ProtobufSchemaValidator::from_descriptor_set(
+            &descriptor_set(".memphis.test.Unknown"),
+            None
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn reads_descriptor_set_from_bytes() {
+        let validator =
+            ProtobufSchemaValidator::from_bytes(&descriptor_set(".memphis.test.Status").into())
+                .unwrap();
+
+        assert_eq!(
+            validator.message_descriptor().full_name(),
+            "memphis.test.Test"
+        );
+        assert!(validator
+            .validate(&Bytes::from_static(VALID_MESSAGE))
+            .is_ok());
+    }
+}
+
+#[cfg(feature = "validator_graphql")]
+mod graphql {
+    use bytes::Bytes;
+    use memphis_rust_community::schemaverse::schema::graphql::GraphQlSchemaValidator;
+    use memphis_rust_community::schemaverse::schema::SchemaValidator;
+
+    const SCHEMA: &str = r#"
+        type Query {
+            payment(id: ID!): Payment
+            payments(limit: Int, status: Status): [Payment!]!
+        }
+
+        type Mutation {
+            refund(id: ID!, amount: Float): Payment
+        }
+
+        type Payment {
+            id: ID!
+            amount: Float!
+            status: Status!
+            customer: Customer
+        }
+
+        type Customer {
+            name: String
+        }
+
+        enum Status {
+            PENDING
+            SETTLED
+        }
+    "#;
+
+    fn validate(query: &'static str) -> bool {
+        let validator = GraphQlSchemaValidator::new(SCHEMA).unwrap();
+        validator
+            .validate(&Bytes::from_static(query.as_bytes()))
+            .is_ok()
+    }
+
+    #[test]
+    fn accepts_valid_documents() {
+        assert!(validate("{ payment(id: 1) { id amount } }"));
+        assert!(validate(
+            "query Payments { payments(limit: 10, status: SETTLED) { id customer { name } } }"
+        ));
+        assert!(validate("mutation { refund(id: 1) { status __typename } }"));
+        assert!(validate(
+            "query { payment(id: 1) { ...PaymentFields } } fragment PaymentFields on Payment { id }"
+        ));
+        assert!(validate(
+            "query { payment(id: 1) { ... on Payment { customer { name } } } }"
+        ));
+    }
+
+    #[test]
+    fn rejects_unknown_fields_and_arguments() {
+        assert!(!validate("{ payment(id: 1) { id currency } }"));
+        assert!(!validate("{ payment(id: 1, currency: EUR) { id } }"));
+        assert!(!validate("{ transfers { id } }"));
+    }
+
+    #[test]
+    fn rejects_invalid_selections() {
+        assert!(!validate("{ payment(id: 1) }"));
+        assert!(!validate("{ payment(id: 1) { id { value } } }"));
+        assert!(!validate("subscription { payment(id: 1) { id } }"));
+        assert!(!validate("{ payment(id: 1) { ...Missing } }"));
+        assert!(!validate(
+            "{ payment(id: 1) { ...A } } fragment A on Payment { ...B } fragment B on Payment { ...A }"
+        ));
+    }
+
+    #[test]
+    fn rejects_malformed_documents() {
+        assert!(!validate("{ payment(id: 1) { id "));
+        assert!(!validate("fragment A on Payment { id }"));
+        assert!(!validate("{\"id\": 1}"));
+    }
+
+    #[test]
+    fn rejects_invalid_schema() {
+        assert!(GraphQlSchemaValidator::new("type Query { payment: Payment }").is_err());
+        assert!(GraphQlSchemaValidator::new("type Query {").is_err());
+        assert!(GraphQlSchemaValidator::from_bytes(&Bytes::from_static(SCHEMA.as_bytes())).is_ok());
+    }
+}
+
+#[cfg(feature = "validator_avro")]
+mod avro {
+    use bytes::Bytes;
+    use memphis_rust_community::schemaverse::schema::avro::AvroSchemaValidator;
+    use memphis_rust_community::schemaverse::schema::SchemaValidator;
+    use serde::{Deserialize, Serialize};
+
+    const SCHEMA: &str = r#"{
+        "type": "record",
+        "name": "Order",
+        "namespace": "memphis.test",
+        "fields": [
+            {"name": "id", "type": "long"},
+            {"name": "quantity", "type": "int"},
+            {"name": "price", "type": "double"},
+            {"name": "weight", "type": "float", "default": 0},
+            {"name": "checksum", "type": {"type": "fixed", "name": "Checksum", "size": 4}, "default": "0000"},
+            {"name": "note", "type": ["null", "string"], "default": null},
+            {"name": "status", "type": {"type": "enum", "name": "Status", "symbols": ["NEW", "PAID"]}},
+            {"name": "items", "type": {"type": "array", "items": "string"}},
+            {"name": "attributes", "type": {"type": "map", "values": "string"}, "default": {}},
+            {"name": "next", "type": ["null", "Order"], "default": null},
+            {"name": "created", "type": {"type": "long", "logicalType": "timestamp-millis"}}
+        ]
+    }"#;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Order {
+        id: i64,
+        quantity: i32,
+        price: f64,
+        note: Option<String>,
+        status: String,
+        items: Vec<String>,
+        created: i64,
+    }
+
+    fn validator() -> AvroSchemaValidator {
+        AvroSchemaValidator::from_bytes(&Bytes::from_static(SCHEMA.as_bytes())).unwrap()
+    }
+
+    fn validate(message: &'static str) -> bool {
+        validator()
+            .validate(&Bytes::from_static(message.as_bytes()))
+            .is_ok()
+    }
+
+    #[test]
+    fn accepts_valid_records() {
+        assert!(validate(
+            r#"{"id": 1, "quantity": 2, "price": 9.5, "status": "NEW", "items": ["a"], "created": 0}"#
+        ));
+        assert!(validate(
+            r#"{"id": 1, "quantity": 2, "price": 9.5, "weight": 1.5, "checksum": "abcd", "status": "NEW",
+                "items": [], "created": 0}"#
+        ));
+        assert!(validate(
+            r#"{"id": 1, "quantity": 2, "price": 9, "note": {"string": "gift"}, "status": "PAID", "items": [],
+                "attributes": {"color": "red"}, "created": 0,
+                "next": {"memphis.test.Order": {"id": 2, "quantity": 1, "price": 1.0, "status": "NEW",
+                    "items": [], "created": 1}}}"#
+        ));
+    }
+
+    #[test]
+    fn rejects_invalid_records() {
+        // quantity exceeds an int
+        assert!(!validate(
+            r#"{"id": 1, "quantity": 2147483648, "price": 9.5, "status": "NEW", "items": [], "created": 0}"#
+        ));
+        // unknown enum symbol
+        assert!(!validate(
+            r#"{"id": 1, "quantity": 2, "price": 9.5, "status": "SHIPPED", "items": [], "created": 0}"#
+        ));
+        // missing field without default
+        assert!(!validate(
+            r#"{"id": 1, "quantity": 2, "price": 9.5, "status": "NEW", "created": 0}"#
+        ));
+        // wrong type in a union
+        assert!(!validate(
+            r#"{"id": 1, "quantity": 2, "price": 9.5, "note": {"string": 1}, "status": "NEW", "items": [], "created": 0}"#
+        ));
+        // union value without its branch
+        assert!(!validate(
+            r#"{"id": 1, "quantity": 2, "price": 9.5, "note": "gift", "status": "NEW", "items": [], "created": 0}"#
+        ));
+        // unknown union branch
+        assert!(!validate(
+            r#"{"id": 1, "quantity": 2, "price": 9.5, "note": {"int": 1}, "status": "NEW", "items": [], "created": 0}"#
+        ));
+        // weight exceeds a float
+        assert!(!validate(
+            r#"{"id": 1, "quantity": 2, "price": 9.5, "weight": 1e39, "status": "NEW", "items": [], "created": 0}"#
+        ));
+        // price is not a number
+        assert!(!validate(
+            r#"{"id": 1, "quantity": 2, "price": "9.5", "status": "NEW", "items": [], "created": 0}"#
+        ));
+        // checksum has 4 characters, but 5 bytes
+        assert!(!validate(
+            r#"{"id": 1, "quantity": 2, "price": 9.5, "checksum": "abcé", "status": "NEW", "items": [], "created": 0}"#
+        ));
+        assert!(!validate("not json"));
+    }
+
+    #[test]
+    fn encodes_and_decodes_records() {
+        let validator = validator();
+        let order = Order {
+            id: 7,
+            quantity: 3,
+            price: 12.5,
+            note: None,
+            status: "PAID".to_string(),
+            items: vec!["book".to_string()],
+            created: 1_700_000_000_000,
+        };
+
+        let message = validator.encode_json(&order).unwrap();
+        assert_eq!(validator.decode_json::<Order>(&message).unwrap(), order);
+
+        let invalid = Order {
+            status: "LOST".to_string(),
+            ..order
+        };
+        assert!(validator.encode_json(&invalid).is_err());
+    }
+
+    #[test]
+    fn rejects_invalid_schema() {
+        let schema = serde_json::json!({"type": "record", "name": "A", "fields": [
+            {"name": "b", "type": "Missing"}
+        ]});
+        assert!(AvroSchemaValidator::new(schema).is_err());
+        assert!(AvroSchemaValidator::new(serde_json::json!({"type": "array"})).is_err());
+        assert!(AvroSchemaValidator::new(serde_json::json!(1)).is_err());
+    }
+
+    #[test]
+    fn rejects_duplicate_type_definitions() {
+        let schema = serde_json::json!({"type": "record", "name": "A", "fields": [
+            {"name": "b", "type": {"type": "enum", "name": "B", "symbols": ["X"]}},
+            {"name": "c", "type": {"type": "fixed", "name": "B", "size": 1}}
+        ]});
+        assert!(AvroSchemaValidator::new(schema).is_err());
+
+        let schema = serde_json::json!({"type": "record", "name": "A", "fields": [
+            {"name": "b", "type": {"type": "record", "name": "A", "fields": []}}
+        ]});
+        assert!(AvroSchemaValidator::new(schema).is_err());
+    }
+}
@@ -32,3 +32,213 @@ async fn test_station_creation() {
         "Creating Station with different Settings should be possible."
     );
 }
+
+#[cfg(feature = "schemaverse")]
+#[tokio::test]
+async fn attach_missing_schema() {
+    let _ = env_logger::try_init();
+
+    let client = connect_to_memphis().await;
+    let station = create_random_station(&client).await;
+
+    let schema_name = uuid::Uuid::new_v4().to_string();
+    tokio_test::assert_err!(
+        station.attach_schema(&schema_name).await,
+        "Attaching a schema that does not exist should fail."
+    );
+}
+
+#[cfg(feature = "schemaverse")]
+#[tokio::test]
+async fn detach_schema() {
+    let _ = env_logger::try_init();
+
+    let client = connect_to_memphis().await;
+    let station = create_random_station(&client).await;
+
+    assert_ok!(
+        station.detach_schema().await,
+        "Detaching the schema of a station should be possible."
+    );
+}
+
+#[cfg(feature = "schemaverse")]
+const PAYMENT_SCHEMA: &str = r#"{
+    "type": "object",
+    "properties": { "amount": { "type": "number" } },
+    "required": ["amount"]
+}"#;
+
+#[cfg(feature = "schemaverse")]
+fn payment(payload: &str) -> memphis_rust_community::producer::ComposableMessage {
+    memphis_rust_community::producer::ComposableMessage::new().with_payload(payload.to_string())
+}
+
+#[cfg(feature = "schemaverse")]
+#[tokio::test]
+async fn attach_schema_validates_messages() {
+    let _ = env_logger::try_init();
+
+    let client = connect_to_memphis().await;
+    let station = create_random_station(&client).await;
+    let mut producer = create_random_producer(&station).await;
+
+    let schema_name = create_random_json_schema(&client, PAYMENT_SCHEMA).await;
+    assert_ok!(
+        station.attach_schema(&schema_name).await,
+        "Attaching a schema should be possible."
+    );
+
+    tokio_test::assert_err!(
+        producer.produce(payment(r#"{"id": 1}"#)).await,
+        "Messages which do not match the attached schema should be rejected."
+    );
+    assert_ok!(
+        producer.produce(payment(r#"{"amount": 10.5}"#)).await,
+        "Messages which match the attached schema should be produced."
+    );
+}
+
+#[cfg(feature = "schemaverse")]
+#[tokio::test]
+async fn detach_schema_stops_validation() {
+    let _ = env_logger::try_init();
+
+    let client = connect_to_memphis().await;
+    let station = create_random_station(&client).await;
+    let mut producer = create_random_producer(&station).await;
+
+    let schema_name = create_random_json_schema(&client, PAYMENT_SCHEMA).await;
+    assert_ok!(station.attach_schema(&schema_name).await);
+    tokio_test::assert_err!(producer.produce(payment(r#"{"id": 1}"#)).await);
+
+    assert_ok!(
+        station.detach_schema().await,
+        "Detaching the schema of a station should be possible."
+    );
+    assert_ok!(
+        producer.produce(payment(r#"{"id": 1}"#)).await,
+        "Messages should not be validated after the schema was detached."
+    );
+}
+
+#[cfg(feature = "schemaverse")]
+#[tokio::test]
+async fn producer_installs_attached_schema() {
+    let _ = env_logger::try_init();
+
+    let station_name = uuid::Uuid::new_v4().to_string();
+    let client = connect_to_memphis().await;
+    let station = assert_ok!(
+        client
+            .create_station(MemphisStationsOptions::new(&station_name))
+            .await
+    );
+    let schema_name = create_random_json_schema(&client, PAYMENT_SCHEMA).await;
+    assert_ok!(station.attach_schema(&schema_name).await);
+
+    // A new client only learns about the schema when the producer is created.
+    let other_client = connect_to_memphis().await;
+    let other_station = assert_ok!(
+        other_client
+            .create_station(MemphisStationsOptions::new(&station_name))
+            .await
+    );
+    let mut producer = create_random_producer(&other_station).await;
+
+    tokio_test::assert_err!(
+        producer.produce(payment(r#"{"id": 1}"#)).await,
+        "The producer should validate messages against the schema attached to the station."
+    );
+    assert_ok!(producer.produce(payment(r#"{"amount": 10.5}"#)).await);
+}
+
+/// Produces the message until the producer accepts or rejects it as expected,
+/// giving the schema update time to arrive.
+#[cfg(feature = "schemaverse")]
+async fn wait_for_validation(
+    producer: &mut memphis_rust_community::producer::MemphisProducer,
+    payload: &str,
+    accepted: bool,
+) -> bool {
+    for _ in 0..50 {
+        if producer.produce(payment(payload)).await.is_ok() == accepted {
+            return true;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+    }
+    false
+}
+
+#[cfg(feature = "schemaverse")]
+#[tokio::test]
+async fn schema_updates_are_applied_to_existing_producers() {
+    let _ = env_logger::try_init();
+
+    let station_name = uuid::Uuid::new_v4().to_string();
+    let client = connect_to_memphis().await;
+    let station = assert_ok!(
+        client
+            .create_station(MemphisStationsOptions::new(&station_name))
+            .await
+    );
+    let mut producer = create_random_producer(&station).await;
+
+    // The schema is changed by another client, so the producer only learns about it
+    // through the schema updates.
+    let other_client = connect_to_memphis().await;
+    let other_station = assert_ok!(
+        other_client
+            .create_station(MemphisStationsOptions::new(&station_name))
+            .await
+    );
+
+    let payment_schema = create_random_json_schema(&other_client, PAYMENT_SCHEMA).await;
+    assert_ok!(other_station.attach_schema(&payment_schema).await);
+    assert!(
+        wait_for_validation(&mut producer, r#"{"id": 1}"#, false).await,
+        "The producer should validate messages against the attached schema."
+    );
+
+    let refund_schema =
+        create_random_json_schema(&other_client, r#"{ "type": "object", "required": ["id"] }"#)
+            .await;
+    assert_ok!(other_station.attach_schema(&refund_schema).await);
+    assert!(
+        wait_for_validation(&mut producer, r#"{"id": 1}"#, true).await,
+        "The producer should validate messages against the new schema."
+    );
+    tokio_test::assert_err!(producer.produce(payment(r#"{"amount": 10.5}"#)).await);
+}
+
+#[cfg(feature = "schemaverse")]
+#[tokio::test]
+async fn schema_detachments_are_applied_to_existing_producers() {
+    let _ = env_logger::try_init();
+
+    let station_name = uuid::Uuid::new_v4().to_string();
+    let client = connect_to_memphis().await;
+    let station = assert_ok!(
+        client
+            .create_station(MemphisStationsOptions::new(&station_name))
+            .await
+    );
+    let mut producer = create_random_producer(&station).await;
+
+    let other_client = connect_to_memphis().await;
+    let other_station = assert_ok!(
+        other_client
+            .create_station(MemphisStationsOptions::new(&station_name))
+            .await
+    );
+
+    let schema_name = create_random_json_schema(&other_client, PAYMENT_SCHEMA).await;
+    assert_ok!(other_station.attach_schema(&schema_name).await);
+    assert!(wait_for_validation(&mut producer, r#"{"id": 1}"#, false).await);
+
+    assert_ok!(other_station.detach_schema().await);
+    assert!(
+        wait_for_validation(&mut producer, r#"{"id": 1}"#, true).await,
+        "The producer should stop validating messages once the schema was detached."
+    );
+}